@@ -0,0 +1,63 @@
+//! Generates FftPrecomp implementations with hardcoded tables for the fields listed in main.
+//! Other fields can use the impl_fft_precomp macro, which computes the same tables at runtime.
+
+use halo2curves::ff::{Field, PrimeField};
+use halo2curves::serde::SerdeObject;
+use std::{env, fs::File, io::Write, path::Path};
+
+/// Emits a match over 0..=S returning raw bytes of the table entries, rejecting indices beyond S.
+fn emit_table<F: PrimeField + SerdeObject>(s: &mut String, arg: &str, table: &[F]) {
+    *s += &format!("        let tmp: &[u8] = match {arg} {{\n");
+    for (i, x) in table.iter().enumerate() {
+        *s += &format!("            {i} => &{:?},\n", x.to_raw_bytes());
+    }
+    *s += &format!(
+        "            _ => panic!(\"{arg} = {{}} exceeds the two-adicity {}\", {arg}),\n",
+        F::S
+    );
+    *s += "        };\n";
+    *s += "        Self::from_raw_bytes_unchecked(tmp)\n";
+}
+
+fn emit_impl<F: PrimeField + SerdeObject>(s: &mut String, name: &str) {
+    let square_powers = |x: F| {
+        let mut acc = x;
+        (0..=F::S)
+            .map(|_| {
+                let tmp = acc;
+                acc = acc.square();
+                tmp
+            })
+            .collect::<Vec<F>>()
+    };
+    let mut acc = F::ONE;
+    let half: Vec<F> = (0..=F::S)
+        .map(|_| {
+            let tmp = acc;
+            acc *= F::TWO_INV;
+            tmp
+        })
+        .collect();
+
+    *s += &format!("impl crate::regular_functions_utils::FftPrecomp for {name} {{\n");
+    *s += "    fn omega_pow(exp2: u32) -> Self {\n";
+    emit_table(s, "exp2", &square_powers(F::ROOT_OF_UNITY));
+    *s += "    }\n\n";
+    *s += "    fn omega_pow_inv(exp2: u32) -> Self {\n";
+    emit_table(s, "exp2", &square_powers(F::ROOT_OF_UNITY_INV));
+    *s += "    }\n\n";
+    *s += "    fn half_pow(exp: u64) -> Self {\n";
+    emit_table(s, "exp", &half);
+    *s += "    }\n";
+    *s += "}\n\n";
+}
+
+fn main() {
+    let mut s = "use halo2curves::serde::SerdeObject;\n\n".to_string();
+    emit_impl::<halo2curves::bn256::Fr>(&mut s, "halo2curves::bn256::Fr");
+
+    let path = Path::new(&env::var("OUT_DIR").unwrap()).join("precomputed_fft_data.rs");
+    let mut f = File::create(path).expect("Unable to create file");
+    f.write_all(s.as_bytes()).expect("Unable to write data");
+    println!("cargo:rerun-if-changed=build.rs");
+}
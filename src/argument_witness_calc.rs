@@ -1,9 +1,12 @@
+use crate::error::MsmWitnessError;
 use crate::negbase_utils;
-use crate::negbase_utils::id_by_digit;
 use crate::negbase_utils::negbase_decompose;
 use crate::negbase_utils::range_check;
+use crate::negbase_utils::DigitSet;
 use crate::regular_functions_utils;
-use crate::regular_functions_utils::compute_divisor_witness;
+use crate::regular_functions_utils::affine_coords;
+use crate::regular_functions_utils::check_short_weierstrass;
+use crate::regular_functions_utils::compute_divisor_witness_unchecked;
 use crate::regular_functions_utils::gen_random_pt;
 use crate::regular_functions_utils::FftPrecomp;
 use crate::regular_functions_utils::RegularFunction;
@@ -12,6 +15,7 @@ use halo2curves::ff::FromUniformBytes;
 use halo2curves::ff::{Field, PrimeField};
 use halo2curves::group::{prime::PrimeCurveAffine, Curve};
 use halo2curves::grumpkin::Fr as Fq;
+use halo2curves::msm::best_multiexp;
 use halo2curves::{bn256, grumpkin, Coordinates, CurveAffine, CurveExt};
 use num_bigint::BigInt;
 use num_bigint::Sign;
@@ -42,15 +46,19 @@ pub fn logb_ceil(x: &BigUint, base: u8) -> u8 {
     i
 }
 
-/// Returns multiplicities of pt, from 1 to base-1
-pub fn precompute_multiplicities<C: CurveExt>(pt: &C, base: u8) -> Vec<C> {
+/// Returns multiplicities of pt for all nonzero digits of the digit set, in increasing order:
+/// from 1 to base-1 for the positive set, from -(base-1)/2 to (base-1)/2 for the symmetric one.
+/// Indexed by DigitSet::id_by_digit.
+pub fn precompute_multiplicities<C: CurveExt>(pt: &C, base: u8, digit_set: DigitSet) -> Vec<C> {
+    let offset = digit_set.offset(base) as usize;
     let mut acc: C = *pt;
     let mut ret = vec![];
-    for _ in 1..base {
+    for _ in 1..(base as usize - offset) {
         ret.push(acc);
         acc = acc + *pt;
     }
-    ret
+    let negative: Vec<C> = ret[..offset].iter().rev().map(|x| -*x).collect();
+    negative.into_iter().chain(ret).collect()
 }
 
 /// Querying order of a prime field is a bit messy, it gives a string of unspecified format. Therefore:
@@ -81,62 +89,144 @@ fn gen_random_coeff<Fz: PrimeField>() -> Fz {
         .fold(Fz::ZERO, |acc, x: Fz| acc * sh + x)
 }
 
+/// Exclusive upper bound on the scalars accepted by compute_lhs_witness, i.e. ceil(sqrt(p)) with a bit of slack.
+pub fn scalar_bound<Fz: PrimeField>() -> BigUint {
+    let p = order::<Fz>();
+    (&p.sqrt() + BigInt::from_bytes_le(Sign::Plus, &[2]))
+        .to_biguint()
+        .unwrap()
+}
+
+/// Amount of negbase digits needed to represent any scalar below scalar_bound.
+/// Symmetric digits are twice smaller, so they need one more digit.
+pub fn num_digits<Fz: PrimeField>(base: u8, digit_set: DigitSet) -> usize {
+    let extra = match digit_set {
+        DigitSet::Positive => 0,
+        DigitSet::Symmetric => 1,
+    };
+    (logb_ceil(&scalar_bound::<Fz>(), base) + 1) as usize + extra
+}
+
+/// Decomposes scalars into num_digits negbase digits each, most significant digit first.
+/// The scalars must be in range between 0 and ceil(sqrt(p)).
+pub fn scalar_digits<Fz: PrimeField>(
+    scalars: &[Fz],
+    base: u8,
+    digit_set: DigitSet,
+) -> Result<Vec<Vec<u8>>, MsmWitnessError> {
+    digit_set.check_base(base)?;
+    let sq_p = scalar_bound::<Fz>();
+    let d = num_digits::<Fz>(base, digit_set);
+
+    scalars
+        .iter()
+        .enumerate()
+        .map(|(index, x)| {
+            let x = BigUint::from_bytes_le(x.to_repr().as_ref());
+            // check that scalars are properly range checked from 0 to sqrt(p)
+            if &x >= &sq_p {
+                return Err(MsmWitnessError::ScalarOutOfRange { index });
+            }
+            let mut digits: Vec<u8> = negbase_decompose(&x.to_bigint().unwrap(), base, digit_set)?
+                .into_iter()
+                .chain(repeat(digit_set.offset(base)))
+                .take(d)
+                .collect();
+            digits.reverse();
+            Ok(digits)
+        })
+        .collect()
+}
+
+/// Returns the carries of the argument after each row, most significant digit first.
+/// The last one is the result of the MSM.
+pub fn compute_carries<C: CurveExt>(
+    digits_by_scalar: &[Vec<u8>],
+    precomputed_points: &[Vec<C>],
+    base: u8,
+    digit_set: DigitSet,
+) -> Result<Vec<C>, MsmWitnessError> {
+    if digits_by_scalar.len() != precomputed_points.len() {
+        return Err(MsmWitnessError::LengthMismatch {
+            expected: precomputed_points.len(),
+            got: digits_by_scalar.len(),
+        });
+    }
+    let d = digits_by_scalar.first().map_or(0, |x| x.len());
+    let mut carry = C::identity();
+    let mut ret = vec![];
+    for i in 0..d {
+        carry = -carry * C::Scalar::from(base as u64);
+        for j in 0..precomputed_points.len() {
+            if let Some(x) = digit_set.id_by_digit(base, digits_by_scalar[j][i]) {
+                carry = carry + precomputed_points[j][x];
+            }
+        }
+        ret.push(carry);
+    }
+    Ok(ret)
+}
+
 /// The core function. It takes a vector of scalars and a vector of points, and returns the witness to lhs of Liam Eagen's
 /// argument, as described in a paper https://eprint.iacr.org/2022/596 , pages 8-9
-/// Few differences: we use arbitrary negbase decomposition, and positive digit set, while Liam's argument uses
+/// Few differences: we use arbitrary negbase decomposition, and by default positive digit set, while Liam's argument uses
 /// -3 negbase and symmetric set of digits (-1, 0, 1). Positive digit set gives an advantage with range checks later
-/// while gains from symmetric digit set are likely negligible. Base > 3 are also needed for better lookups.
-/// The scalars are assumed to be in range between 0 and ceil(sqrt(p)).
+/// while gains from symmetric digit set are likely negligible; both are supported to be able to compare them.
+/// Base > 3 are also needed for better lookups.
+/// The scalars must be in range between 0 and ceil(sqrt(p)), otherwise an error is returned.
+/// The rows are independent once the carries are known, so their witnesses are computed in parallel.
+/// The curve is checked once here, and not again for every row.
 pub fn compute_lhs_witness<C: CurveExt>(
     scalars: &[C::Scalar],
     pts: &[C],
     base: u8,
-) -> (C, Vec<RegularFunction<C>>)
+    digit_set: DigitSet,
+) -> Result<(C, Vec<RegularFunction<C>>), MsmWitnessError>
 where
     C::Base: FftPrecomp,
 {
-    assert!(
-        scalars.len() == pts.len(),
-        "incompatible amount of coefficients"
-    );
-    let p = order::<C::Scalar>();
-    let sq_p = (&p.sqrt() + BigInt::from_bytes_le(Sign::Plus, &[2]))
-        .to_biguint()
-        .unwrap();
-    let d = logb_ceil(&sq_p, base) + 1; // amount of digits
+    check_short_weierstrass::<C>()?;
+    let (carry, rows) = lhs_rows(scalars, pts, base, digit_set)?;
 
-    let scalars = scalars
+    let mut ret: Vec<Result<RegularFunction<C>, MsmWitnessError>> = rows
         .iter()
-        .map(|x| BigUint::from_bytes_le(x.to_repr().as_ref()));
-
-    // check that scalars are properly range checked from 0 to sqrt(p)
-    let _: Vec<_> = scalars.clone().map(|x| assert!(&x < &sq_p)).collect();
-
-    let mut digits_by_scalar: Vec<Vec<u8>> = scalars
-        .clone()
-        .map(|x| {
-            negbase_decompose(&x.to_bigint().unwrap(), base)
-                .into_iter()
-                .chain(repeat(0 as u8))
-                .take(d.into())
-                .collect()
-        })
+        .map(|_| Err(MsmWitnessError::EmptyInput))
         .collect();
+    rayon_core::scope(|scope| {
+        for (row, res) in rows.iter().zip(ret.iter_mut()) {
+            scope.spawn(move |_| *res = compute_divisor_witness_unchecked(row));
+        }
+    });
 
-    (&mut digits_by_scalar)
-        .iter_mut()
-        .map(|x| x.reverse())
-        .count();
+    Ok((carry, ret.into_iter().collect::<Result<_, _>>()?))
+}
+
+/// Computes the carries and returns the final one together with the points the witness of each row vanishes in,
+/// last row first, i.e. in the order of the witnesses returned by compute_lhs_witness.
+fn lhs_rows<C: CurveExt>(
+    scalars: &[C::Scalar],
+    pts: &[C],
+    base: u8,
+    digit_set: DigitSet,
+) -> Result<(C, Vec<Vec<C>>), MsmWitnessError> {
+    if scalars.len() != pts.len() {
+        return Err(MsmWitnessError::LengthMismatch {
+            expected: pts.len(),
+            got: scalars.len(),
+        });
+    }
+    let digits_by_scalar = scalar_digits(scalars, base, digit_set)?;
+    let d = num_digits::<C::Scalar>(base, digit_set); // amount of digits
 
     let precomputed_points: Vec<Vec<C>> = pts
         .into_iter()
-        .map(|pt| precompute_multiplicities(pt, base))
+        .map(|pt| precompute_multiplicities(pt, base, digit_set))
         .collect();
 
     let mut carry = C::identity();
     let mut ret = vec![];
 
-    for i in 0..(d as usize) {
+    for i in 0..d {
         let mut tmp = Vec::<C>::new();
 
         if carry != C::identity() {
@@ -145,24 +235,416 @@ where
             }
         }
 
-        carry = (-carry * felt_from_u64::<C::Scalar>(base as u64)).into();
+        carry = -carry * felt_from_u64::<C::Scalar>(base as u64);
 
         for j in 0..pts.len() {
-            match id_by_digit(digits_by_scalar[j][i]) {
+            match digit_set.id_by_digit(base, digits_by_scalar[j][i]) {
                 None => (),
                 Some(x) => {
                     tmp.push(precomputed_points[j][x]);
-                    carry = (carry + precomputed_points[j][x]).into()
+                    carry = carry + precomputed_points[j][x]
                 }
             }
         }
 
         tmp.push(-carry);
 
-        ret.push(compute_divisor_witness(&tmp));
+        ret.push(tmp);
     }
 
     ret.reverse();
 
-    (carry, ret)
-}
\ No newline at end of file
+    Ok((carry, ret))
+}
+
+/// Converts a number below the order into a field element.
+fn felt_from_biguint<Fz: PrimeField>(x: &BigUint) -> Fz {
+    let sh: Fz = felt_from_u64(pow(2, 32));
+    x.to_u32_digits()
+        .into_iter()
+        .rev()
+        .map(|x| felt_from_u64(x as u64))
+        .fold(Fz::ZERO, |acc, x: Fz| acc * sh + x)
+}
+
+/// Full-size scalars split into halves accepted by compute_lhs_witness. The i-th scalar s_i = lo_i + shift * hi_i
+/// is replaced by lo_i = scalars[2i] paired with pts[2i] = P_i and hi_i = scalars[2i+1] paired with
+/// pts[2i+1] = shift * P_i. Shift is isqrt(p) + 1, so both halves are below scalar_bound, and the circuit
+/// has to constrain s_i = lo_i + shift * hi_i itself.
+#[derive(Clone, Debug)]
+pub struct ScalarSplit<C: CurveExt> {
+    pub scalars: Vec<C::Scalar>,
+    pub pts: Vec<C>,
+    pub shift: C::Scalar,
+}
+
+/// Splits arbitrary scalars into halves, see ScalarSplit. Curves with an efficient endomorphism could use
+/// the GLV split instead; the halves it produces can be fed to compute_lhs_witness in the same way.
+pub fn split_scalars<C: CurveExt>(
+    scalars: &[C::Scalar],
+    pts: &[C],
+) -> Result<ScalarSplit<C>, MsmWitnessError> {
+    if scalars.len() != pts.len() {
+        return Err(MsmWitnessError::LengthMismatch {
+            expected: pts.len(),
+            got: scalars.len(),
+        });
+    }
+    let shift_int = order::<C::Scalar>().sqrt().to_biguint().unwrap() + BigUint::one();
+    let shift: C::Scalar = felt_from_biguint(&shift_int);
+
+    let mut split = ScalarSplit {
+        scalars: vec![],
+        pts: vec![],
+        shift,
+    };
+    for (sc, pt) in scalars.iter().zip(pts) {
+        let sc = BigUint::from_bytes_le(sc.to_repr().as_ref());
+        split.scalars.push(felt_from_biguint(&(&sc % &shift_int)));
+        split.scalars.push(felt_from_biguint(&(&sc / &shift_int)));
+        split.pts.push(*pt);
+        split.pts.push(*pt * shift);
+    }
+    Ok(split)
+}
+
+/// Same as compute_lhs_witness, but accepts arbitrary scalars, splitting them with split_scalars first.
+/// The split is returned along with the witness, the witness is computed for split.scalars and split.pts.
+pub fn compute_lhs_witness_full<C: CurveExt>(
+    scalars: &[C::Scalar],
+    pts: &[C],
+    base: u8,
+    digit_set: DigitSet,
+) -> Result<(C, Vec<RegularFunction<C>>, ScalarSplit<C>), MsmWitnessError>
+where
+    C::Base: FftPrecomp,
+{
+    let split = split_scalars(scalars, pts)?;
+    let (carry, wtns) = compute_lhs_witness(&split.scalars, &split.pts, base, digit_set)?;
+    Ok((carry, wtns, split))
+}
+
+/// A line y = lambda * x + mu passing through a pair of points and minus their sum,
+/// used as a challenge for the argument.
+#[derive(Clone, Debug)]
+pub struct ChallengeLine<C: CurveExt> {
+    pub pts: [C; 3],
+    pub lambda: C::Base,
+    pub mu: C::Base,
+}
+
+impl<C: CurveExt> ChallengeLine<C> {
+    /// Fails if one of the points is the identity, or they have the same x coordinate.
+    pub fn new(a0: C, a1: C) -> Result<Self, MsmWitnessError> {
+        let (x0, y0) = affine_coords(&a0).ok_or(MsmWitnessError::DegenerateChallenge)?;
+        let (x1, y1) = affine_coords(&a1).ok_or(MsmWitnessError::DegenerateChallenge)?;
+        let dx: Option<C::Base> = (x1 - x0).invert().into();
+        let lambda = (y1 - y0) * dx.ok_or(MsmWitnessError::DegenerateChallenge)?;
+        let mu = y0 - lambda * x0;
+        Ok(ChallengeLine {
+            pts: [a0, a1, -(a0 + a1)],
+            lambda,
+            mu,
+        })
+    }
+
+    pub fn random() -> Self {
+        loop {
+            if let Ok(line) = Self::new(gen_random_pt(), gen_random_pt()) {
+                return line;
+            }
+        }
+    }
+
+    /// returns y - lambda * x - mu, fails at the identity
+    pub fn ev(&self, pt: C) -> Result<C::Base, MsmWitnessError> {
+        let (x, y) = affine_coords(&pt).ok_or(MsmWitnessError::DegenerateChallenge)?;
+        Ok(y - self.lambda * x - self.mu)
+    }
+}
+
+/// Evaluates sum dlog f(A) over the points of the challenge line, for every row of the witness
+/// (in the order returned by compute_lhs_witness). Fails if the line passes through a zero of the witness.
+pub fn compute_lhs_dlog<C: CurveExt>(
+    wtns: &[RegularFunction<C>],
+    line: &ChallengeLine<C>,
+) -> Result<Vec<C::Base>, MsmWitnessError>
+where
+    C::Base: FftPrecomp,
+{
+    wtns.iter()
+        .map(|f| {
+            let dlogs = f.ev_dlog_many(&line.pts, line.lambda)?;
+            Ok(dlogs.into_iter().fold(C::Base::ZERO, |acc, v| acc + v))
+        })
+        .collect()
+}
+
+/// Computes the rhs of the argument: for every row, minus the sum of 1 / l(P) over the points P of the row's divisor,
+/// counted with multiplicities (the previous carry comes with weight base). The last carry is replaced
+/// by the claimed result. Rows are in the order returned by compute_lhs_witness.
+/// Fails with DegenerateChallenge if the line passes through one of these points.
+pub fn compute_rhs_witness<C: CurveExt>(
+    scalars: &[C::Scalar],
+    pts: &[C],
+    base: u8,
+    digit_set: DigitSet,
+    result: C,
+    line: &ChallengeLine<C>,
+) -> Result<Vec<C::Base>, MsmWitnessError> {
+    if scalars.len() != pts.len() {
+        return Err(MsmWitnessError::LengthMismatch {
+            expected: pts.len(),
+            got: scalars.len(),
+        });
+    }
+    let digits_by_scalar = scalar_digits(scalars, base, digit_set)?;
+    let precomputed_points: Vec<Vec<C>> = pts
+        .iter()
+        .map(|pt| precompute_multiplicities(pt, base, digit_set))
+        .collect();
+    let mut carries = compute_carries(&digits_by_scalar, &precomputed_points, base, digit_set)?;
+    let d = carries.len();
+    carries[d - 1] = result;
+
+    let line_inv = |pt: C| -> Result<C::Base, MsmWitnessError> {
+        if pt == C::identity() {
+            return Ok(C::Base::ZERO);
+        }
+        Option::from(line.ev(pt)?.invert()).ok_or(MsmWitnessError::DegenerateChallenge)
+    };
+
+    let mut ret = vec![];
+    for i in 0..d {
+        let mut acc = line_inv(-carries[i])?;
+        if i > 0 {
+            acc += C::Base::from(base as u64) * line_inv(-carries[i - 1])?;
+        }
+        for j in 0..pts.len() {
+            if let Some(x) = digit_set.id_by_digit(base, digits_by_scalar[j][i]) {
+                acc += line_inv(precomputed_points[j][x])?;
+            }
+        }
+        ret.push(-acc);
+    }
+
+    ret.reverse();
+    Ok(ret)
+}
+
+/// Out-of-circuit verifier of the argument: checks that the witness proves sum scalars_i * pts_i = result,
+/// by comparing logarithmic derivatives of the witness with the rhs at the challenge line.
+/// On failure, reports the first row (in the order returned by compute_lhs_witness) that doesn't match.
+pub fn check_argument<C: CurveExt>(
+    scalars: &[C::Scalar],
+    pts: &[C],
+    base: u8,
+    digit_set: DigitSet,
+    result: C,
+    wtns: &[RegularFunction<C>],
+    line: &ChallengeLine<C>,
+) -> Result<(), MsmWitnessError>
+where
+    C::Base: FftPrecomp,
+{
+    let rhs = compute_rhs_witness(scalars, pts, base, digit_set, result, line)?;
+    if wtns.len() != rhs.len() {
+        return Err(MsmWitnessError::LengthMismatch {
+            expected: rhs.len(),
+            got: wtns.len(),
+        });
+    }
+    let lhs = compute_lhs_dlog(wtns, line)?;
+    for row in 0..rhs.len() {
+        if lhs[row] != rhs[row] {
+            return Err(MsmWitnessError::RowMismatch { row });
+        }
+    }
+    Ok(())
+}
+
+#[test]
+
+fn argument_test() {
+    let scalars: Vec<Fq> = (0..10).map(|_| gen_random_coeff()).collect();
+    let pts: Vec<Grumpkin> = (0..10).map(|_| gen_random_pt()).collect();
+    let (carry, wtns) = compute_lhs_witness(&scalars, &pts, 5, DigitSet::Positive).unwrap();
+
+    let bases: Vec<grumpkin::G1Affine> = pts.iter().map(|x| x.into()).collect();
+    assert!(carry == best_multiexp(&scalars, &bases));
+
+    let line = ChallengeLine::random();
+    assert!(check_argument(&scalars, &pts, 5, DigitSet::Positive, carry, &wtns, &line) == Ok(()));
+}
+
+#[test]
+
+fn argument_secp256r1_test() {
+    use halo2curves::group::Group;
+    use halo2curves::secp256r1::{Fq as P256Scalar, Secp256r1};
+
+    let gen = || Secp256r1::generator() * P256Scalar::random(OsRng);
+    let scalars: Vec<P256Scalar> = (0..10).map(|_| gen_random_coeff()).collect();
+    let pts: Vec<Secp256r1> = (0..10).map(|_| gen()).collect();
+    let (carry, wtns) = compute_lhs_witness(&scalars, &pts, 5, DigitSet::Positive).unwrap();
+
+    let expected = pts
+        .iter()
+        .zip(scalars.iter())
+        .fold(Secp256r1::identity(), |acc, (pt, sc)| acc + *pt * sc);
+    assert!(carry == expected);
+
+    let line = ChallengeLine::new(gen(), gen()).unwrap();
+    assert!(check_argument(&scalars, &pts, 5, DigitSet::Positive, carry, &wtns, &line) == Ok(()));
+}
+
+#[test]
+
+fn argument_wrong_result_test() {
+    let scalars: Vec<Fq> = (0..10).map(|_| gen_random_coeff()).collect();
+    let pts: Vec<Grumpkin> = (0..10).map(|_| gen_random_pt()).collect();
+    let (carry, wtns) = compute_lhs_witness(&scalars, &pts, 5, DigitSet::Positive).unwrap();
+
+    let line = ChallengeLine::random();
+    // the last row comes first
+    assert_eq!(
+        check_argument(
+            &scalars,
+            &pts,
+            5,
+            DigitSet::Positive,
+            carry + pts[0],
+            &wtns,
+            &line
+        ),
+        Err(MsmWitnessError::RowMismatch { row: 0 })
+    );
+
+    let mut wtns = wtns;
+    wtns.swap(3, 4);
+    assert_eq!(
+        check_argument(&scalars, &pts, 5, DigitSet::Positive, carry, &wtns, &line),
+        Err(MsmWitnessError::RowMismatch { row: 3 })
+    );
+
+    // a challenge passing through minus the result, which is a zero of the first row
+    let line = ChallengeLine::new(-carry, gen_random_pt()).unwrap();
+    assert_eq!(
+        check_argument(&scalars, &pts, 5, DigitSet::Positive, carry, &wtns, &line),
+        Err(MsmWitnessError::DegenerateChallenge)
+    );
+}
+
+#[test]
+
+fn lhs_witness_errors_test() {
+    let mut scalars: Vec<Fq> = (0..4).map(|_| gen_random_coeff()).collect();
+    let pts: Vec<Grumpkin> = (0..3).map(|_| gen_random_pt()).collect();
+    assert!(matches!(
+        compute_lhs_witness(&scalars, &pts, 5, DigitSet::Positive),
+        Err(MsmWitnessError::LengthMismatch {
+            expected: 3,
+            got: 4
+        })
+    ));
+
+    scalars.pop();
+    scalars[1] = -Fq::ONE;
+    assert!(matches!(
+        compute_lhs_witness(&scalars, &pts, 5, DigitSet::Positive),
+        Err(MsmWitnessError::ScalarOutOfRange { index: 1 })
+    ));
+    assert!(matches!(
+        compute_lhs_witness(&scalars, &pts, 1, DigitSet::Positive),
+        Err(MsmWitnessError::InvalidBase { base: 1 })
+    ));
+
+    let pt: Grumpkin = gen_random_pt();
+    assert!(matches!(
+        ChallengeLine::new(pt, -pt),
+        Err(MsmWitnessError::DegenerateChallenge)
+    ));
+}
+
+#[test]
+
+fn full_scalars_test() {
+    let scalars: Vec<Fq> = (0..5).map(|_| Fq::random(OsRng)).collect();
+    let pts: Vec<Grumpkin> = (0..5).map(|_| gen_random_pt()).collect();
+    let (carry, wtns, split) =
+        compute_lhs_witness_full(&scalars, &pts, 5, DigitSet::Positive).unwrap();
+
+    let bases: Vec<grumpkin::G1Affine> = pts.iter().map(|x| x.into()).collect();
+    assert!(carry == best_multiexp(&scalars, &bases));
+
+    for i in 0..scalars.len() {
+        assert!(split.scalars[2 * i] + split.shift * split.scalars[2 * i + 1] == scalars[i]);
+        assert!(split.pts[2 * i + 1] == pts[i] * split.shift);
+    }
+
+    let line = ChallengeLine::random();
+    assert!(
+        check_argument(
+            &split.scalars,
+            &split.pts,
+            5,
+            DigitSet::Positive,
+            carry,
+            &wtns,
+            &line
+        ) == Ok(())
+    );
+}
+
+#[test]
+
+fn argument_symmetric_test() {
+    let scalars: Vec<Fq> = (0..10).map(|_| gen_random_coeff()).collect();
+    let pts: Vec<Grumpkin> = (0..10).map(|_| gen_random_pt()).collect();
+    let bases: Vec<grumpkin::G1Affine> = pts.iter().map(|x| x.into()).collect();
+    let line = ChallengeLine::random();
+
+    for base in [3, 5] {
+        let (carry, wtns) = compute_lhs_witness(&scalars, &pts, base, DigitSet::Symmetric).unwrap();
+        assert!(carry == best_multiexp(&scalars, &bases));
+        assert!(
+            check_argument(
+                &scalars,
+                &pts,
+                base,
+                DigitSet::Symmetric,
+                carry,
+                &wtns,
+                &line
+            ) == Ok(())
+        );
+    }
+}
+
+#[test]
+#[ignore = "benchmark, run with --release -- --ignored"]
+
+fn lhs_witness_bench() {
+    for logn in 10..=16 {
+        let scalars: Vec<Fq> = (0..1 << logn).map(|_| gen_random_coeff()).collect();
+        let pts: Vec<Grumpkin> = (0..1 << logn).map(|_| gen_random_pt()).collect();
+
+        let start = SystemTime::now();
+        let (_, rows) = lhs_rows(&scalars, &pts, 5, DigitSet::Positive).unwrap();
+        let seq: Vec<RegularFunction<Grumpkin>> = rows
+            .iter()
+            .map(|row| compute_divisor_witness_unchecked(row).unwrap())
+            .collect();
+        let seq_ms = start.elapsed().unwrap().as_millis();
+
+        let start = SystemTime::now();
+        let (_, par) = compute_lhs_witness(&scalars, &pts, 5, DigitSet::Positive).unwrap();
+        let par_ms = start.elapsed().unwrap().as_millis();
+
+        assert!(seq == par);
+        println!(
+            "lhs witness for 2^{} points: {} ms sequential, {} ms parallel",
+            logn, seq_ms, par_ms
+        );
+    }
+}
@@ -0,0 +1,83 @@
+use std::fmt::{Display, Formatter};
+
+/// Errors returned by the witness computation.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MsmWitnessError {
+    /// points passed to the divisor computation do not sum to the identity;
+    /// residual is the hex-encoded affine sum, or "identity"
+    NonZeroSum { residual: String },
+    /// nothing to merge
+    EmptyInput,
+    /// scalar with this index is not below ceil(sqrt(p))
+    ScalarOutOfRange { index: usize },
+    /// a value other than a scalar (limb, digit id) does not fit into the expected range
+    ValueOutOfRange,
+    /// amounts of scalars and points (or rows) do not agree
+    LengthMismatch { expected: usize, got: usize },
+    /// negbase decomposition needs base >= 2
+    InvalidBase { base: u8 },
+    /// the scalar needs more digits than allowed
+    TooManyDigits { digits: usize, max: usize },
+    /// the challenge points are the identity or have the same x coordinate
+    DegenerateChallenge,
+    /// lhs and rhs of the argument differ in this row
+    RowMismatch { row: usize },
+    /// polynomial division that was expected to be exact has a nonzero remainder
+    InexactDivision,
+    /// polynomial of this degree does not fit into the given amount of coefficients
+    DegreeBound { degree: usize, coeffs: usize },
+    /// power series with a zero constant term has no inverse
+    NotInvertible,
+    /// two points of a Mumford representation have the same x coordinate
+    DuplicateX,
+    /// u is not a product of distinct linear factors, or doesn't divide v^2 - (x^3 + ax + b)
+    InvalidMumford,
+    /// the divisor is not principal, i.e. its points don't sum to the identity
+    NotPrincipal,
+    /// a point of order 2 is repeated, which the linear algebra divisor algorithm doesn't support
+    RepeatedTwoTorsion,
+    /// the curve is not a nonsingular curve y^2 = x^3 + ax + b with a, b given by CurveExt
+    NotShortWeierstrass,
+}
+
+impl Display for MsmWitnessError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        match self {
+            MsmWitnessError::NonZeroSum { residual } => {
+                write!(f, "points sum to {} instead of the identity", residual)
+            }
+            MsmWitnessError::EmptyInput => write!(f, "empty input"),
+            MsmWitnessError::ScalarOutOfRange { index } => {
+                write!(f, "scalar {} is not below ceil(sqrt(p))", index)
+            }
+            MsmWitnessError::ValueOutOfRange => write!(f, "value out of range"),
+            MsmWitnessError::LengthMismatch { expected, got } => {
+                write!(f, "expected {} elements, got {}", expected, got)
+            }
+            MsmWitnessError::InvalidBase { base } => write!(f, "invalid base {}", base),
+            MsmWitnessError::TooManyDigits { digits, max } => {
+                write!(f, "{} digits, at most {} allowed", digits, max)
+            }
+            MsmWitnessError::DegenerateChallenge => write!(f, "degenerate challenge"),
+            MsmWitnessError::RowMismatch { row } => {
+                write!(f, "argument does not hold in row {}", row)
+            }
+            MsmWitnessError::InexactDivision => write!(f, "division has a nonzero remainder"),
+            MsmWitnessError::DegreeBound { degree, coeffs } => write!(
+                f,
+                "polynomial of degree {} does not fit into {} coefficients",
+                degree, coeffs
+            ),
+            MsmWitnessError::NotInvertible => write!(f, "power series is not invertible"),
+            MsmWitnessError::DuplicateX => write!(f, "points with the same x coordinate"),
+            MsmWitnessError::InvalidMumford => write!(f, "invalid Mumford representation"),
+            MsmWitnessError::NotPrincipal => write!(f, "divisor is not principal"),
+            MsmWitnessError::RepeatedTwoTorsion => write!(f, "repeated point of order 2"),
+            MsmWitnessError::NotShortWeierstrass => {
+                write!(f, "curve is not in short Weierstrass form")
+            }
+        }
+    }
+}
+
+impl std::error::Error for MsmWitnessError {}
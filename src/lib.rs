@@ -1,7 +1,10 @@
 pub mod argument_witness_calc;
+pub mod error;
+pub mod msm_chip;
+pub mod mumford;
 pub mod negbase_utils;
 pub mod regular_functions_utils;
+#[cfg(test)]
+mod test_curve;
 
 pub mod precomputed_fft_data;
-
-mod scripts;
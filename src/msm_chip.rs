@@ -0,0 +1,1214 @@
+use crate::argument_witness_calc::{
+    compute_carries, compute_lhs_witness, num_digits, precompute_multiplicities, scalar_digits,
+};
+use crate::negbase_utils::DigitSet;
+use crate::regular_functions_utils::{
+    affine_coords, gen_random_pt, FftPrecomp, Grumpkin, Polynomial, RegularFunction,
+};
+use halo2_proofs::{
+    circuit::{AssignedCell, Chip, Layouter, SimpleFloorPlanner, Value},
+    dev::MockProver,
+    plonk::{
+        Advice, Challenge, Circuit, Column, ConstraintSystem, Error, Expression, FirstPhase, Fixed,
+        Instance, SecondPhase, Selector, TableColumn, VirtualCells,
+    },
+    poly::Rotation,
+};
+use halo2curves::bn256::Fr as F;
+use halo2curves::ff::{Field, PrimeField};
+use halo2curves::group::Curve;
+use halo2curves::grumpkin::{self, Fr as Fq};
+use halo2curves::msm::best_multiexp;
+use halo2curves::{CurveAffine, CurveExt};
+use rand::random;
+use std::{cmp, iter::*, marker::PhantomData};
+
+/// The random points of the argument are sampled as (gamma + k, y) with k < 2^OFFSET_BITS,
+/// the prover picks the first k for which x^3 + ax + b is a square.
+pub const OFFSET_BITS: usize = 8;
+
+/// Layout of the chip. Everything the prover commits to before seeing the challenges (points, their multiples,
+/// digits, carries and divisor coefficients) lives in the first phase, everything derived from the random
+/// line lives in the second phase.
+///
+/// The region consists of:
+/// 1) a single row with the random line through A0 = (gamma0 + k0, y0), A1 = (gamma1 + k1, y1) and A2,
+///    its parameters are then copied down the whole region
+/// 2) a block per point with the multiples kP, the inverses 1/l(kP) (the lookup table for the digits),
+///    digits of the scalar and the running sums for the scalar and the digit part of the identity
+/// 3) a block per digit row with the coefficients of the divisor witness, evaluated via Horner at A0, A1, A2
+///    together with the derivatives; the last row checks the log-derivative identity for this row
+/// 4) a few rows summing everything together.
+/// Rows of the identity are combined with powers of the challenge r.
+#[derive(Clone, Debug)]
+pub struct LiamEagenMsmConfig {
+    base: u8,
+
+    // first phase
+    coeff_a: Column<Advice>,
+    coeff_b: Column<Advice>,
+    digit: Column<Advice>,
+    scalar_acc: Column<Advice>,
+    px: Column<Advice>,
+    py: Column<Advice>,
+    mx: Column<Advice>,
+    my: Column<Advice>,
+    mlam: Column<Advice>,
+    cx: Column<Advice>,
+    cy: Column<Advice>,
+    cid: Column<Advice>,
+
+    // second phase
+    koff: [Column<Advice>; 2],
+    lam: Column<Advice>,
+    mu: Column<Advice>,
+    xs: [Column<Advice>; 3],
+    ys: [Column<Advice>; 3],
+    ea: [Column<Advice>; 3],
+    da: [Column<Advice>; 3],
+    eb: [Column<Advice>; 3],
+    db: [Column<Advice>; 3],
+    finv: [Column<Advice>; 3],
+    linv: Column<Advice>,
+    dterm: Column<Advice>,
+    dacc: Column<Advice>,
+    cinv: Column<Advice>,
+    cinv_prev: Column<Advice>,
+    acc_in: Column<Advice>,
+    acc_out: Column<Advice>,
+
+    tag: Column<Fixed>,
+    kcol: Column<Fixed>,
+    range_table: TableColumn,
+
+    challenges: [Challenge; 3],
+
+    q_glob: Selector,
+    q_bcast: Selector,
+    q_minit: Selector,
+    q_dbl: Selector,
+    q_add: Selector,
+    q_pcopy: Selector,
+    q_table: Selector,
+    q_linv: Selector,
+    q_digit: Selector,
+    q_hinit: Selector,
+    q_hstep: Selector,
+    q_row: Selector,
+    q_fsum: Selector,
+}
+
+/// Cells produced by the chip, so the caller can bind them to the rest of the circuit.
+#[derive(Clone, Debug)]
+pub struct AssignedMsm<Fz: Field> {
+    /// affine coordinates of the input points
+    pub points: Vec<(AssignedCell<Fz, Fz>, AssignedCell<Fz, Fz>)>,
+    /// scalars recomposed from the digits
+    pub scalars: Vec<AssignedCell<Fz, Fz>>,
+    /// x, y of the result and a boolean flag which is 1 iff the result is the identity
+    pub result: (
+        AssignedCell<Fz, Fz>,
+        AssignedCell<Fz, Fz>,
+        AssignedCell<Fz, Fz>,
+    ),
+}
+
+/// Chip proving sum s_i * P_i = Q using the witness produced by compute_lhs_witness.
+/// The curve is C, the circuit is defined over its base field (i.e. Grumpkin inside of a BN254 circuit).
+/// The scalars are subject to the same restriction as in compute_lhs_witness: they must be below ceil(sqrt(p)).
+/// Only the positive digit set is supported, digits are range checked to be below base.
+/// Additions used to compute multiples kP are incomplete, which is fine for points of large prime order.
+pub struct LiamEagenMsmChip<C: CurveExt>
+where
+    C::Base: FftPrecomp,
+{
+    config: LiamEagenMsmConfig,
+    _marker: PhantomData<C>,
+}
+
+impl<C: CurveExt> Chip<C::Base> for LiamEagenMsmChip<C>
+where
+    C::Base: FftPrecomp,
+{
+    type Config = LiamEagenMsmConfig;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+/// x^3 + ax + b and its derivative
+fn curve_rhs<C: CurveExt>(x: Expression<C::Base>) -> (Expression<C::Base>, Expression<C::Base>) {
+    let a = Expression::Constant(C::a());
+    let b = Expression::Constant(C::b());
+    let three = Expression::Constant(C::Base::from(3));
+    (
+        x.clone() * x.clone() * x.clone() + a.clone() * x.clone() + b,
+        three * x.clone() * x + a,
+    )
+}
+
+impl<C: CurveExt> LiamEagenMsmChip<C>
+where
+    C::Base: FftPrecomp,
+{
+    pub fn construct(config: LiamEagenMsmConfig) -> Self {
+        LiamEagenMsmChip {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(meta: &mut ConstraintSystem<C::Base>, base: u8) -> LiamEagenMsmConfig {
+        assert!(base >= 2, "base must be at least 2");
+
+        let coeff_a = meta.advice_column();
+        let coeff_b = meta.advice_column();
+        let digit = meta.advice_column();
+        let scalar_acc = meta.advice_column();
+        let px = meta.advice_column();
+        let py = meta.advice_column();
+        let mx = meta.advice_column();
+        let my = meta.advice_column();
+        let mlam = meta.advice_column();
+        let cx = meta.advice_column();
+        let cy = meta.advice_column();
+        let cid = meta.advice_column();
+
+        let challenges = [
+            meta.challenge_usable_after(FirstPhase),
+            meta.challenge_usable_after(FirstPhase),
+            meta.challenge_usable_after(FirstPhase),
+        ];
+
+        let mut second_phase = || meta.advice_column_in(SecondPhase);
+        let koff = [second_phase(), second_phase()];
+        let lam = second_phase();
+        let mu = second_phase();
+        let xs = [second_phase(), second_phase(), second_phase()];
+        let ys = [second_phase(), second_phase(), second_phase()];
+        let ea = [second_phase(), second_phase(), second_phase()];
+        let da = [second_phase(), second_phase(), second_phase()];
+        let eb = [second_phase(), second_phase(), second_phase()];
+        let db = [second_phase(), second_phase(), second_phase()];
+        let finv = [second_phase(), second_phase(), second_phase()];
+        let linv = second_phase();
+        let dterm = second_phase();
+        let dacc = second_phase();
+        let cinv = second_phase();
+        let cinv_prev = second_phase();
+        let acc_in = second_phase();
+        let acc_out = second_phase();
+
+        let tag = meta.fixed_column();
+        let kcol = meta.fixed_column();
+        let constants = meta.fixed_column();
+        meta.enable_constant(constants);
+        let range_table = meta.lookup_table_column();
+
+        for col in [
+            px, py, scalar_acc, cx, cy, cid, linv, dacc, cinv, cinv_prev, acc_in, acc_out,
+        ] {
+            meta.enable_equality(col);
+        }
+
+        let config = LiamEagenMsmConfig {
+            base,
+            coeff_a,
+            coeff_b,
+            digit,
+            scalar_acc,
+            px,
+            py,
+            mx,
+            my,
+            mlam,
+            cx,
+            cy,
+            cid,
+            koff,
+            lam,
+            mu,
+            xs,
+            ys,
+            ea,
+            da,
+            eb,
+            db,
+            finv,
+            linv,
+            dterm,
+            dacc,
+            cinv,
+            cinv_prev,
+            acc_in,
+            acc_out,
+            tag,
+            kcol,
+            range_table,
+            challenges,
+            q_glob: meta.complex_selector(),
+            q_bcast: meta.selector(),
+            q_minit: meta.selector(),
+            q_dbl: meta.selector(),
+            q_add: meta.selector(),
+            q_pcopy: meta.selector(),
+            q_table: meta.complex_selector(),
+            q_linv: meta.selector(),
+            q_digit: meta.complex_selector(),
+            q_hinit: meta.selector(),
+            q_hstep: meta.selector(),
+            q_row: meta.selector(),
+            q_fsum: meta.selector(),
+        };
+
+        let c = &config;
+        let base_felt = Expression::Constant(C::Base::from(base as u64));
+
+        meta.create_gate("random line", |meta| {
+            let q = meta.query_selector(c.q_glob);
+            let g = c.challenges.map(|ch| meta.query_challenge(ch));
+            let k = c.koff.map(|col| meta.query_advice(col, Rotation::cur()));
+            let x = c.xs.map(|col| meta.query_advice(col, Rotation::cur()));
+            let y = c.ys.map(|col| meta.query_advice(col, Rotation::cur()));
+            let lam = meta.query_advice(c.lam, Rotation::cur());
+            let mu = meta.query_advice(c.mu, Rotation::cur());
+            let (g0, _) = curve_rhs::<C>(x[0].clone());
+            let (g1, _) = curve_rhs::<C>(x[1].clone());
+
+            vec![
+                x[0].clone() - g[0].clone() - k[0].clone(),
+                x[1].clone() - g[1].clone() - k[1].clone(),
+                y[0].clone() * y[0].clone() - g0,
+                y[1].clone() * y[1].clone() - g1,
+                lam.clone() * (x[1].clone() - x[0].clone()) - (y[1].clone() - y[0].clone()),
+                mu.clone() - y[0].clone() + lam.clone() * x[0].clone(),
+                x[2].clone() - lam.clone() * lam.clone() + x[0].clone() + x[1].clone(),
+                y[2].clone() - lam * x[2].clone() - mu,
+            ]
+            .into_iter()
+            .map(|e| q.clone() * e)
+            .collect::<Vec<_>>()
+        });
+
+        for i in 0..2 {
+            meta.lookup("challenge offset", |meta| {
+                let q = meta.query_selector(c.q_glob);
+                let k = meta.query_advice(c.koff[i], Rotation::cur());
+                vec![(q * k, c.range_table)]
+            });
+        }
+
+        meta.create_gate("broadcast line", |meta| {
+            let q = meta.query_selector(c.q_bcast);
+            [c.lam, c.mu]
+                .into_iter()
+                .chain(c.xs)
+                .chain(c.ys)
+                .map(|col| {
+                    q.clone()
+                        * (meta.query_advice(col, Rotation::cur())
+                            - meta.query_advice(col, Rotation::prev()))
+                })
+                .collect::<Vec<_>>()
+        });
+
+        meta.create_gate("point on curve", |meta| {
+            let q = meta.query_selector(c.q_minit);
+            let px = meta.query_advice(c.px, Rotation::cur());
+            let py = meta.query_advice(c.py, Rotation::cur());
+            let mx = meta.query_advice(c.mx, Rotation::cur());
+            let my = meta.query_advice(c.my, Rotation::cur());
+            let (g, _) = curve_rhs::<C>(px.clone());
+            vec![
+                q.clone() * (mx - px),
+                q.clone() * (my - py.clone()),
+                q * (py.clone() * py - g),
+            ]
+        });
+
+        // (x, y) of the next row is computed from the slope mlam
+        let next_multiple = |meta: &mut VirtualCells<C::Base>| {
+            let px = meta.query_advice(c.px, Rotation::cur());
+            let mx = meta.query_advice(c.mx, Rotation::cur());
+            let my = meta.query_advice(c.my, Rotation::cur());
+            let mlam = meta.query_advice(c.mlam, Rotation::cur());
+            let mx_next = meta.query_advice(c.mx, Rotation::next());
+            let my_next = meta.query_advice(c.my, Rotation::next());
+            [
+                mx_next.clone() - mlam.clone() * mlam.clone() + mx.clone() + px,
+                my_next - mlam * (mx - mx_next) + my,
+            ]
+        };
+
+        meta.create_gate("doubling", |meta| {
+            let q = meta.query_selector(c.q_dbl);
+            let mx = meta.query_advice(c.mx, Rotation::cur());
+            let my = meta.query_advice(c.my, Rotation::cur());
+            let mlam = meta.query_advice(c.mlam, Rotation::cur());
+            let (_, dg) = curve_rhs::<C>(mx);
+            let [nx, ny] = next_multiple(meta);
+            vec![
+                q.clone() * (mlam * Expression::Constant(C::Base::from(2)) * my - dg),
+                q.clone() * nx,
+                q * ny,
+            ]
+        });
+
+        meta.create_gate("incomplete addition", |meta| {
+            let q = meta.query_selector(c.q_add);
+            let px = meta.query_advice(c.px, Rotation::cur());
+            let py = meta.query_advice(c.py, Rotation::cur());
+            let mx = meta.query_advice(c.mx, Rotation::cur());
+            let my = meta.query_advice(c.my, Rotation::cur());
+            let mlam = meta.query_advice(c.mlam, Rotation::cur());
+            let [nx, ny] = next_multiple(meta);
+            vec![
+                q.clone() * (mlam * (mx - px) - (my - py)),
+                q.clone() * nx,
+                q * ny,
+            ]
+        });
+
+        meta.create_gate("copy point", |meta| {
+            let q = meta.query_selector(c.q_pcopy);
+            [c.px, c.py]
+                .map(|col| {
+                    q.clone()
+                        * (meta.query_advice(col, Rotation::cur())
+                            - meta.query_advice(col, Rotation::prev()))
+                })
+                .to_vec()
+        });
+
+        meta.create_gate("inverse of line at multiple", |meta| {
+            let q = meta.query_selector(c.q_linv);
+            let mx = meta.query_advice(c.mx, Rotation::cur());
+            let my = meta.query_advice(c.my, Rotation::cur());
+            let linv = meta.query_advice(c.linv, Rotation::cur());
+            let lam = meta.query_advice(c.lam, Rotation::cur());
+            let mu = meta.query_advice(c.mu, Rotation::cur());
+            vec![q * (linv * (my - lam * mx - mu) - Expression::Constant(C::Base::ONE))]
+        });
+
+        meta.create_gate("digit", |meta| {
+            let q = meta.query_selector(c.q_digit);
+            let r = meta.query_challenge(c.challenges[2]);
+            let digit = meta.query_advice(c.digit, Rotation::cur());
+            let acc = meta.query_advice(c.scalar_acc, Rotation::cur());
+            let acc_prev = meta.query_advice(c.scalar_acc, Rotation::prev());
+            let dterm = meta.query_advice(c.dterm, Rotation::cur());
+            let dacc = meta.query_advice(c.dacc, Rotation::cur());
+            let dacc_prev = meta.query_advice(c.dacc, Rotation::prev());
+            vec![
+                q.clone() * (acc - (acc_prev * (-base_felt.clone()) + digit)),
+                q * (dacc - (dacc_prev * r + dterm)),
+            ]
+        });
+
+        // (point tag, digit, 1/l(digit * P)) must be in the table of multiples, which also range checks the digit
+        meta.lookup_any("digit table", |meta| {
+            let q_d = meta.query_selector(c.q_digit);
+            let q_t = meta.query_selector(c.q_table);
+            let tag = meta.query_fixed(c.tag, Rotation::cur());
+            vec![
+                (q_d.clone() * tag.clone(), q_t.clone() * tag),
+                (
+                    q_d.clone() * meta.query_advice(c.digit, Rotation::cur()),
+                    q_t.clone() * meta.query_fixed(c.kcol, Rotation::cur()),
+                ),
+                (
+                    q_d * meta.query_advice(c.dterm, Rotation::cur()),
+                    q_t * meta.query_advice(c.linv, Rotation::cur()),
+                ),
+            ]
+        });
+
+        meta.create_gate("horner init", |meta| {
+            let q = meta.query_selector(c.q_hinit);
+            let ca = meta.query_advice(c.coeff_a, Rotation::cur());
+            let cb = meta.query_advice(c.coeff_b, Rotation::cur());
+            let mut ret = vec![];
+            for j in 0..3 {
+                ret.push(q.clone() * (meta.query_advice(c.ea[j], Rotation::cur()) - ca.clone()));
+                ret.push(q.clone() * meta.query_advice(c.da[j], Rotation::cur()));
+                ret.push(q.clone() * (meta.query_advice(c.eb[j], Rotation::cur()) - cb.clone()));
+                ret.push(q.clone() * meta.query_advice(c.db[j], Rotation::cur()));
+            }
+            ret
+        });
+
+        meta.create_gate("horner step", |meta| {
+            let q = meta.query_selector(c.q_hstep);
+            let ca = meta.query_advice(c.coeff_a, Rotation::cur());
+            let cb = meta.query_advice(c.coeff_b, Rotation::cur());
+            let mut ret = vec![];
+            for j in 0..3 {
+                let x = meta.query_advice(c.xs[j], Rotation::cur());
+                for (ev, dv, coeff) in [
+                    (c.ea[j], c.da[j], ca.clone()),
+                    (c.eb[j], c.db[j], cb.clone()),
+                ] {
+                    let ev_cur = meta.query_advice(ev, Rotation::cur());
+                    let ev_prev = meta.query_advice(ev, Rotation::prev());
+                    let dv_cur = meta.query_advice(dv, Rotation::cur());
+                    let dv_prev = meta.query_advice(dv, Rotation::prev());
+                    ret.push(q.clone() * (ev_cur - ev_prev.clone() * x.clone() - coeff));
+                    ret.push(q.clone() * (dv_cur - dv_prev * x.clone() - ev_prev));
+                }
+            }
+            ret
+        });
+
+        meta.create_gate("log-derivative row", |meta| {
+            let q = meta.query_selector(c.q_row);
+            let r = meta.query_challenge(c.challenges[2]);
+            let lam = meta.query_advice(c.lam, Rotation::cur());
+            let mu = meta.query_advice(c.mu, Rotation::cur());
+            let one = Expression::Constant(C::Base::ONE);
+            let two = Expression::Constant(C::Base::from(2));
+
+            let mut ret = vec![];
+            let mut lhs = Expression::Constant(C::Base::ZERO);
+            for j in 0..3 {
+                let x = meta.query_advice(c.xs[j], Rotation::cur());
+                let y = meta.query_advice(c.ys[j], Rotation::cur());
+                let ea = meta.query_advice(c.ea[j], Rotation::cur());
+                let da = meta.query_advice(c.da[j], Rotation::cur());
+                let eb = meta.query_advice(c.eb[j], Rotation::cur());
+                let db = meta.query_advice(c.db[j], Rotation::cur());
+                let finv = meta.query_advice(c.finv[j], Rotation::cur());
+                let (g, dg) = curve_rhs::<C>(x);
+                // f(A) and the derivative of the curve equation along the line
+                let f = ea + y.clone() * eb.clone();
+                let h = dg.clone() - two.clone() * lam.clone() * y.clone();
+                ret.push(q.clone() * (finv.clone() * f * h - one.clone()));
+                // df / omega, where omega = dx / 2y is the invariant differential
+                let df = two.clone() * g * db + dg * eb + two.clone() * y * da;
+                lhs = lhs + df * finv;
+            }
+
+            let cx = meta.query_advice(c.cx, Rotation::cur());
+            let cy = meta.query_advice(c.cy, Rotation::cur());
+            let cid = meta.query_advice(c.cid, Rotation::cur());
+            let cinv = meta.query_advice(c.cinv, Rotation::cur());
+            let cinv_prev = meta.query_advice(c.cinv_prev, Rotation::cur());
+            let acc_in = meta.query_advice(c.acc_in, Rotation::cur());
+            let acc_out = meta.query_advice(c.acc_out, Rotation::cur());
+
+            // cinv = 1 / l(-C) unless C is the identity
+            ret.push(
+                q.clone() * (cinv.clone() * (-cy - lam * cx - mu) - (one.clone() - cid.clone())),
+            );
+            ret.push(q.clone() * (cid.clone() * (one - cid.clone())));
+            ret.push(q.clone() * (cid * cinv.clone()));
+            ret.push(q * (acc_out - (acc_in * r + lhs + base_felt.clone() * cinv_prev + cinv)));
+            ret
+        });
+
+        meta.create_gate("final sum", |meta| {
+            let q = meta.query_selector(c.q_fsum);
+            let acc_in = meta.query_advice(c.acc_in, Rotation::cur());
+            let acc_out = meta.query_advice(c.acc_out, Rotation::cur());
+            let dacc = meta.query_advice(c.dacc, Rotation::cur());
+            vec![q * (acc_out - acc_in - dacc)]
+        });
+
+        config
+    }
+
+    /// Amount of rows used for the coefficients of a single divisor witness.
+    /// A row has at most base + n + 1 points and a has at least as many coefficients as b.
+    pub fn coeff_rows(n: usize, base: u8) -> usize {
+        RegularFunction::<C>::num_coeffs(base as usize + n + 1).0
+    }
+
+    /// Amount of rows used by assign for n points, not counting the range table.
+    pub fn num_rows(n: usize, base: u8) -> usize {
+        let d = num_digits::<C::Scalar>(base, DigitSet::Positive);
+        let h_p = cmp::max(d + 1, base as usize);
+        1 + n * h_p + d * Self::coeff_rows(n, base) + n
+    }
+
+    pub fn load_range_table(&self, layouter: &mut impl Layouter<C::Base>) -> Result<(), Error> {
+        layouter.assign_table(
+            || "challenge offset range",
+            |mut table| {
+                for i in 0..(1 << OFFSET_BITS) {
+                    table.assign_cell(
+                        || "offset",
+                        self.config.range_table,
+                        i,
+                        || Value::known(C::Base::from(i as u64)),
+                    )?;
+                }
+                Ok(())
+            },
+        )
+    }
+
+    pub fn assign(
+        &self,
+        mut layouter: impl Layouter<C::Base>,
+        scalars: &[Value<C::Scalar>],
+        points: &[Value<C>],
+    ) -> Result<AssignedMsm<C::Base>, Error> {
+        let c = &self.config;
+        let base = c.base;
+        let n = points.len();
+        if n == 0 || scalars.len() != n {
+            return Err(Error::Synthesis);
+        }
+        let d = num_digits::<C::Scalar>(base, DigitSet::Positive);
+        let l = Self::coeff_rows(n, base);
+        let h_p = cmp::max(d + 1, base as usize);
+
+        let scalars: Value<Vec<C::Scalar>> = scalars.iter().copied().collect();
+        let points: Value<Vec<C>> = points.iter().copied().collect();
+        let wtns = scalars
+            .zip(points)
+            .map(|(s, p)| FirstPhaseWitness::<C>::compute(&s, &p, base, d, l));
+        wtns.error_if_known_and(|w| w.is_none())?;
+        let wtns = wtns.map(|w| w.unwrap());
+
+        let challenges = layouter
+            .get_challenge(c.challenges[0])
+            .zip(layouter.get_challenge(c.challenges[1]))
+            .zip(layouter.get_challenge(c.challenges[2]));
+        let aux = wtns
+            .as_ref()
+            .zip(challenges)
+            .map(|(w, ((g0, g1), r))| SecondPhaseWitness::compute(w, g0, g1, r, base));
+
+        layouter.assign_region(
+            || "liam eagen msm",
+            |mut region| {
+                let zero = C::Base::ZERO;
+                let points_start = 1;
+                let rows_start = points_start + n * h_p;
+                let sum_start = rows_start + d * l;
+                let total = sum_start + n;
+
+                // the random line
+                c.q_glob.enable(&mut region, 0)?;
+                for i in 0..2 {
+                    region.assign_advice(
+                        || "k",
+                        c.koff[i],
+                        0,
+                        || aux.as_ref().map(|a| a.koff[i]),
+                    )?;
+                }
+                for row in 0..total {
+                    if row > 0 {
+                        c.q_bcast.enable(&mut region, row)?;
+                    }
+                    region.assign_advice(
+                        || "lambda",
+                        c.lam,
+                        row,
+                        || aux.as_ref().map(|a| a.lam),
+                    )?;
+                    region.assign_advice(|| "mu", c.mu, row, || aux.as_ref().map(|a| a.mu))?;
+                    for j in 0..3 {
+                        region.assign_advice(
+                            || "x",
+                            c.xs[j],
+                            row,
+                            || aux.as_ref().map(|a| a.xs[j]),
+                        )?;
+                        region.assign_advice(
+                            || "y",
+                            c.ys[j],
+                            row,
+                            || aux.as_ref().map(|a| a.ys[j]),
+                        )?;
+                    }
+                }
+
+                // blocks of points
+                let mut assigned_points = vec![];
+                let mut assigned_scalars = vec![];
+                let mut daccs = vec![];
+                for i in 0..n {
+                    let start = points_start + i * h_p;
+                    let tag = Value::known(C::Base::from(i as u64 + 1));
+                    for row in 0..h_p {
+                        region.assign_fixed(|| "tag", c.tag, start + row, || tag)?;
+                    }
+
+                    // table of multiples
+                    for k in 0..(base as usize) {
+                        let off = start + k;
+                        c.q_table.enable(&mut region, off)?;
+                        region.assign_fixed(
+                            || "k",
+                            c.kcol,
+                            off,
+                            || Value::known(C::Base::from(k as u64)),
+                        )?;
+                        if k == 0 {
+                            region.assign_advice_from_constant(|| "linv", c.linv, off, zero)?;
+                            continue;
+                        }
+                        c.q_linv.enable(&mut region, off)?;
+                        region.assign_advice(
+                            || "linv",
+                            c.linv,
+                            off,
+                            || aux.as_ref().map(|a| a.linv[i][k]),
+                        )?;
+                        region.assign_advice(
+                            || "mx",
+                            c.mx,
+                            off,
+                            || wtns.as_ref().map(|w| w.multiples[i][k - 1].0),
+                        )?;
+                        region.assign_advice(
+                            || "my",
+                            c.my,
+                            off,
+                            || wtns.as_ref().map(|w| w.multiples[i][k - 1].1),
+                        )?;
+                        let px = region.assign_advice(
+                            || "px",
+                            c.px,
+                            off,
+                            || wtns.as_ref().map(|w| w.multiples[i][0].0),
+                        )?;
+                        let py = region.assign_advice(
+                            || "py",
+                            c.py,
+                            off,
+                            || wtns.as_ref().map(|w| w.multiples[i][0].1),
+                        )?;
+                        if k == 1 {
+                            c.q_minit.enable(&mut region, off)?;
+                            assigned_points.push((px, py));
+                        } else {
+                            c.q_pcopy.enable(&mut region, off)?;
+                        }
+                        if k + 1 < base as usize {
+                            if k == 1 {
+                                c.q_dbl.enable(&mut region, off)?;
+                            } else {
+                                c.q_add.enable(&mut region, off)?;
+                            }
+                            region.assign_advice(
+                                || "mlam",
+                                c.mlam,
+                                off,
+                                || wtns.as_ref().map(|w| w.mlams[i][k - 1]),
+                            )?;
+                        }
+                    }
+
+                    // digits
+                    region.assign_advice_from_constant(|| "scalar", c.scalar_acc, start, zero)?;
+                    region.assign_advice_from_constant(|| "dacc", c.dacc, start, zero)?;
+                    let mut scalar = None;
+                    let mut dacc = None;
+                    for t in 0..d {
+                        let off = start + t + 1;
+                        c.q_digit.enable(&mut region, off)?;
+                        region.assign_advice(
+                            || "digit",
+                            c.digit,
+                            off,
+                            || wtns.as_ref().map(|w| C::Base::from(w.digits[i][t] as u64)),
+                        )?;
+                        scalar = Some(region.assign_advice(
+                            || "scalar",
+                            c.scalar_acc,
+                            off,
+                            || wtns.as_ref().map(|w| w.scalar_accs[i][t]),
+                        )?);
+                        region.assign_advice(
+                            || "dterm",
+                            c.dterm,
+                            off,
+                            || aux.as_ref().map(|a| a.dterms[i][t]),
+                        )?;
+                        dacc = Some(region.assign_advice(
+                            || "dacc",
+                            c.dacc,
+                            off,
+                            || aux.as_ref().map(|a| a.daccs[i][t]),
+                        )?);
+                    }
+                    assigned_scalars.push(scalar.unwrap());
+                    daccs.push(dacc.unwrap());
+                }
+
+                // blocks of divisor witnesses
+                let mut acc_prev = None;
+                let mut cinv_prev = None;
+                let mut result = None;
+                for t in 0..d {
+                    let start = rows_start + t * l;
+                    for row in 0..l {
+                        let off = start + row;
+                        if row == 0 {
+                            c.q_hinit.enable(&mut region, off)?;
+                        } else {
+                            c.q_hstep.enable(&mut region, off)?;
+                        }
+                        region.assign_advice(
+                            || "a",
+                            c.coeff_a,
+                            off,
+                            || wtns.as_ref().map(|w| w.coeffs[t].0[row]),
+                        )?;
+                        region.assign_advice(
+                            || "b",
+                            c.coeff_b,
+                            off,
+                            || wtns.as_ref().map(|w| w.coeffs[t].1[row]),
+                        )?;
+                        for j in 0..3 {
+                            let h = aux.as_ref().map(|a| a.horner[t][row][j]);
+                            region.assign_advice(|| "a(x)", c.ea[j], off, || h.map(|h| h[0]))?;
+                            region.assign_advice(|| "a'(x)", c.da[j], off, || h.map(|h| h[1]))?;
+                            region.assign_advice(|| "b(x)", c.eb[j], off, || h.map(|h| h[2]))?;
+                            region.assign_advice(|| "b'(x)", c.db[j], off, || h.map(|h| h[3]))?;
+                        }
+                    }
+
+                    let off = start + l - 1;
+                    c.q_row.enable(&mut region, off)?;
+                    for j in 0..3 {
+                        region.assign_advice(
+                            || "finv",
+                            c.finv[j],
+                            off,
+                            || aux.as_ref().map(|a| a.finvs[t][j]),
+                        )?;
+                    }
+                    let carry = wtns.as_ref().map(|w| w.carries[t]);
+                    let cx = region.assign_advice(
+                        || "cx",
+                        c.cx,
+                        off,
+                        || carry.map(|p| p.map_or(zero, |p| p.0)),
+                    )?;
+                    let cy = region.assign_advice(
+                        || "cy",
+                        c.cy,
+                        off,
+                        || carry.map(|p| p.map_or(zero, |p| p.1)),
+                    )?;
+                    let cid = region.assign_advice(
+                        || "cid",
+                        c.cid,
+                        off,
+                        || carry.map(|p| if p.is_none() { C::Base::ONE } else { zero }),
+                    )?;
+                    let cinv = region.assign_advice(
+                        || "cinv",
+                        c.cinv,
+                        off,
+                        || aux.as_ref().map(|a| a.cinvs[t]),
+                    )?;
+                    match &cinv_prev {
+                        None => {
+                            region.assign_advice_from_constant(
+                                || "cinv_prev",
+                                c.cinv_prev,
+                                off,
+                                zero,
+                            )?;
+                        }
+                        Some(cell) => {
+                            cell.copy_advice(|| "cinv_prev", &mut region, c.cinv_prev, off)?;
+                        }
+                    }
+                    match &acc_prev {
+                        None => {
+                            region.assign_advice_from_constant(|| "acc_in", c.acc_in, off, zero)?;
+                        }
+                        Some(cell) => {
+                            cell.copy_advice(|| "acc_in", &mut region, c.acc_in, off)?;
+                        }
+                    }
+                    acc_prev = Some(region.assign_advice(
+                        || "acc_out",
+                        c.acc_out,
+                        off,
+                        || aux.as_ref().map(|a| a.accs[t]),
+                    )?);
+                    cinv_prev = Some(cinv);
+                    result = Some((cx, cy, cid));
+                }
+
+                // summing the row part with the digit parts
+                let mut acc_value = aux.as_ref().map(|a| a.accs[d - 1]);
+                for i in 0..n {
+                    let off = sum_start + i;
+                    c.q_fsum.enable(&mut region, off)?;
+                    acc_prev.as_ref().unwrap().copy_advice(
+                        || "acc_in",
+                        &mut region,
+                        c.acc_in,
+                        off,
+                    )?;
+                    daccs[i].copy_advice(|| "dacc", &mut region, c.dacc, off)?;
+                    acc_value = acc_value + aux.as_ref().map(|a| a.daccs[i][d - 1]);
+                    acc_prev =
+                        Some(region.assign_advice(|| "acc_out", c.acc_out, off, || acc_value)?);
+                }
+                region.constrain_constant(acc_prev.unwrap().cell(), zero)?;
+
+                Ok(AssignedMsm {
+                    points: assigned_points,
+                    scalars: assigned_scalars,
+                    result: result.unwrap(),
+                })
+            },
+        )
+    }
+}
+
+/// Evaluates a polynomial given by coefficients from the highest degree, together with its derivative.
+/// Returns all the intermediate values, as they are laid out by the chip.
+fn horner<Fz: Field>(coeffs: &[Fz], x: Fz) -> Vec<(Fz, Fz)> {
+    let mut ev = Fz::ZERO;
+    let mut dev = Fz::ZERO;
+    coeffs
+        .iter()
+        .map(|coeff| {
+            dev = dev * x + ev;
+            ev = ev * x + coeff;
+            (ev, dev)
+        })
+        .collect()
+}
+
+/// Searches for a point with x-coordinate gamma + k, k < 2^OFFSET_BITS. Returns (k, x, y).
+fn point_near<C: CurveExt>(gamma: C::Base) -> (C::Base, C::Base, C::Base) {
+    for k in 0..(1 << OFFSET_BITS) {
+        let k = C::Base::from(k as u64);
+        let x = gamma + k;
+        let y: Option<C::Base> = (x * x * x + C::a() * x + C::b()).sqrt().into();
+        if let Some(y) = y {
+            return (k, x, y);
+        }
+    }
+    panic!("no point found near the challenge")
+}
+
+struct FirstPhaseWitness<C: CurveExt> {
+    /// digits[i][t], most significant digit first
+    digits: Vec<Vec<u8>>,
+    scalar_accs: Vec<Vec<C::Base>>,
+    /// multiples[i][k-1] = k P_i
+    multiples: Vec<Vec<(C::Base, C::Base)>>,
+    /// mlams[i][k-1] is the slope used to compute (k+1) P_i from k P_i
+    mlams: Vec<Vec<C::Base>>,
+    /// carries[t] after processing t-th row, None for the identity
+    carries: Vec<Option<(C::Base, C::Base)>>,
+    /// coefficients of a and b, highest degree first, padded to the same length
+    coeffs: Vec<(Vec<C::Base>, Vec<C::Base>)>,
+}
+
+impl<C: CurveExt> FirstPhaseWitness<C>
+where
+    C::Base: FftPrecomp,
+{
+    fn compute(scalars: &[C::Scalar], pts: &[C], base: u8, d: usize, l: usize) -> Option<Self> {
+        let digits = scalar_digits(scalars, base, DigitSet::Positive).ok()?;
+        let scalar_accs = digits
+            .iter()
+            .map(|tmp| {
+                let mut acc = C::Base::ZERO;
+                tmp.iter()
+                    .map(|digit| {
+                        acc = -acc * C::Base::from(base as u64) + C::Base::from(*digit as u64);
+                        acc
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let precomputed_points: Vec<Vec<C>> = pts
+            .iter()
+            .map(|pt| precompute_multiplicities(pt, base, DigitSet::Positive))
+            .collect();
+        let multiples: Vec<Vec<(C::Base, C::Base)>> = precomputed_points
+            .iter()
+            .map(|m| m.iter().map(affine_coords).collect::<Option<Vec<_>>>())
+            .collect::<Option<Vec<_>>>()?;
+
+        let mut mlams = vec![];
+        for m in multiples.iter() {
+            let (px, py) = m[0];
+            let mut tmp = vec![];
+            for k in 1..(base as usize - 1) {
+                let (x, y) = m[k - 1];
+                let slope: Option<C::Base> = if k == 1 {
+                    (y + y)
+                        .invert()
+                        .map(|inv| (C::Base::from(3) * x * x + C::a()) * inv)
+                        .into()
+                } else {
+                    (x - px).invert().map(|inv| (y - py) * inv).into()
+                };
+                tmp.push(slope?);
+            }
+            mlams.push(tmp);
+        }
+
+        let carries = compute_carries(&digits, &precomputed_points, base, DigitSet::Positive)
+            .ok()?
+            .iter()
+            .map(affine_coords)
+            .collect();
+
+        let (_, wtns) = compute_lhs_witness(scalars, pts, base, DigitSet::Positive).ok()?;
+        let mut coeffs = vec![];
+        for t in 0..d {
+            // compute_lhs_witness returns witnesses starting from the least significant digit
+            let f = &wtns[d - 1 - t];
+            let pad = |p: &Polynomial<C::Base>| -> Option<Vec<C::Base>> {
+                let mut ret = p.padded(l).ok()?;
+                ret.reverse();
+                Some(ret)
+            };
+            coeffs.push((pad(&f.a)?, pad(&f.b)?));
+        }
+
+        Some(FirstPhaseWitness {
+            digits,
+            scalar_accs,
+            multiples,
+            mlams,
+            carries,
+            coeffs,
+        })
+    }
+}
+
+struct SecondPhaseWitness<Fz: Field> {
+    koff: [Fz; 2],
+    lam: Fz,
+    mu: Fz,
+    xs: [Fz; 3],
+    ys: [Fz; 3],
+    /// linv[i][k] = 1 / l(k P_i), 0 for k = 0
+    linv: Vec<Vec<Fz>>,
+    dterms: Vec<Vec<Fz>>,
+    daccs: Vec<Vec<Fz>>,
+    /// horner[t][row][j] = (a(x_j), a'(x_j), b(x_j), b'(x_j)) evaluated up to the row
+    horner: Vec<Vec<[[Fz; 4]; 3]>>,
+    finvs: Vec<[Fz; 3]>,
+    cinvs: Vec<Fz>,
+    accs: Vec<Fz>,
+}
+
+impl<Fz: PrimeField> SecondPhaseWitness<Fz> {
+    fn compute<C: CurveExt<Base = Fz>>(
+        w: &FirstPhaseWitness<C>,
+        g0: Fz,
+        g1: Fz,
+        r: Fz,
+        base: u8,
+    ) -> Self {
+        let (k0, x0, y0) = point_near::<C>(g0);
+        let (k1, x1, y1) = point_near::<C>(g1);
+        let lam = (y1 - y0) * (x1 - x0).invert().unwrap();
+        let mu = y0 - lam * x0;
+        let x2 = lam * lam - x0 - x1;
+        let y2 = lam * x2 + mu;
+        let xs = [x0, x1, x2];
+        let ys = [y0, y1, y2];
+        let line_inv = |(x, y): (Fz, Fz)| (y - lam * x - mu).invert().unwrap();
+
+        let linv: Vec<Vec<Fz>> = w
+            .multiples
+            .iter()
+            .map(|m| {
+                once(Fz::ZERO)
+                    .chain(m.iter().map(|pt| line_inv(*pt)))
+                    .collect()
+            })
+            .collect();
+
+        let mut dterms = vec![];
+        let mut daccs = vec![];
+        for (digits, linv) in w.digits.iter().zip(linv.iter()) {
+            let terms: Vec<Fz> = digits.iter().map(|digit| linv[*digit as usize]).collect();
+            let mut acc = Fz::ZERO;
+            daccs.push(
+                terms
+                    .iter()
+                    .map(|term| {
+                        acc = acc * r + term;
+                        acc
+                    })
+                    .collect(),
+            );
+            dterms.push(terms);
+        }
+
+        let three = Fz::from(3);
+        let mut horners = vec![];
+        let mut finvs = vec![];
+        let mut cinvs = vec![];
+        let mut accs = vec![];
+        let mut acc = Fz::ZERO;
+        let mut cinv_prev = Fz::ZERO;
+        for t in 0..w.coeffs.len() {
+            let (a, b) = &w.coeffs[t];
+            let ev: Vec<(Vec<(Fz, Fz)>, Vec<(Fz, Fz)>)> =
+                xs.iter().map(|x| (horner(a, *x), horner(b, *x))).collect();
+            horners.push(
+                (0..a.len())
+                    .map(|row| {
+                        [0, 1, 2].map(|j| {
+                            let (ea, da) = ev[j].0[row];
+                            let (eb, db) = ev[j].1[row];
+                            [ea, da, eb, db]
+                        })
+                    })
+                    .collect(),
+            );
+
+            let mut lhs = Fz::ZERO;
+            let finv = [0, 1, 2].map(|j| {
+                let (x, y) = (xs[j], ys[j]);
+                let (ea, da) = *ev[j].0.last().unwrap();
+                let (eb, db) = *ev[j].1.last().unwrap();
+                let g = x * x * x + C::a() * x + C::b();
+                let dg = three * x * x + C::a();
+                let finv = ((ea + y * eb) * (dg - (lam + lam) * y)).invert().unwrap();
+                lhs += (g.double() * db + dg * eb + y.double() * da) * finv;
+                finv
+            });
+            let cinv = match w.carries[t] {
+                None => Fz::ZERO,
+                Some((x, y)) => line_inv((x, -y)),
+            };
+            acc = acc * r + lhs + Fz::from(base as u64) * cinv_prev + cinv;
+
+            finvs.push(finv);
+            cinvs.push(cinv);
+            accs.push(acc);
+            cinv_prev = cinv;
+        }
+
+        SecondPhaseWitness {
+            koff: [k0, k1],
+            lam,
+            mu,
+            xs,
+            ys,
+            linv,
+            dterms,
+            daccs,
+            horner: horners,
+            finvs,
+            cinvs,
+            accs,
+        }
+    }
+}
+
+#[cfg(test)]
+#[derive(Clone)]
+struct MsmTestCircuit<const BASE: u8> {
+    scalars: Vec<Value<Fq>>,
+    points: Vec<Value<Grumpkin>>,
+}
+
+#[cfg(test)]
+impl<const BASE: u8> Circuit<F> for MsmTestCircuit<BASE> {
+    type Config = (LiamEagenMsmConfig, Column<Instance>);
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        MsmTestCircuit {
+            scalars: vec![Value::unknown(); self.scalars.len()],
+            points: vec![Value::unknown(); self.points.len()],
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+        (
+            LiamEagenMsmChip::<Grumpkin>::configure(meta, BASE),
+            instance,
+        )
+    }
+
+    fn synthesize(
+        &self,
+        (config, instance): Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = LiamEagenMsmChip::<Grumpkin>::construct(config);
+        chip.load_range_table(&mut layouter)?;
+        let res = chip.assign(layouter.namespace(|| "msm"), &self.scalars, &self.points)?;
+        layouter.constrain_instance(res.result.0.cell(), instance, 0)?;
+        layouter.constrain_instance(res.result.1.cell(), instance, 1)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+fn msm_test_instance(n: usize) -> (Vec<Fq>, Vec<Grumpkin>, Vec<F>) {
+    // 126-bit scalars are below sqrt(p)
+    let scalars: Vec<Fq> = (0..n)
+        .map(|_| Fq::from_u128(random::<u128>() >> 2))
+        .collect();
+    let pts: Vec<Grumpkin> = (0..n).map(|_| gen_random_pt()).collect();
+    let bases: Vec<grumpkin::G1Affine> = pts.iter().map(|x| x.to_affine()).collect();
+    let res = best_multiexp(&scalars, &bases).to_affine();
+    let coords = res.coordinates().unwrap();
+    (scalars, pts, vec![*coords.x(), *coords.y()])
+}
+
+#[test]
+
+fn msm_chip_test() {
+    let (scalars, pts, instance) = msm_test_instance(4);
+    let circuit = MsmTestCircuit::<3> {
+        scalars: scalars.into_iter().map(Value::known).collect(),
+        points: pts.into_iter().map(Value::known).collect(),
+    };
+    let prover = MockProver::run(11, &circuit, vec![instance]).unwrap();
+    prover.assert_satisfied();
+}
+
+#[test]
+
+fn msm_chip_larger_base_test() {
+    let (mut scalars, pts, _) = msm_test_instance(5);
+    scalars[2] = Fq::ZERO;
+    let bases: Vec<grumpkin::G1Affine> = pts.iter().map(|x| x.to_affine()).collect();
+    let res = best_multiexp(&scalars, &bases).to_affine();
+    let coords = res.coordinates().unwrap();
+    let circuit = MsmTestCircuit::<7> {
+        scalars: scalars.into_iter().map(Value::known).collect(),
+        points: pts.into_iter().map(Value::known).collect(),
+    };
+    let prover = MockProver::run(11, &circuit, vec![vec![*coords.x(), *coords.y()]]).unwrap();
+    prover.assert_satisfied();
+}
+
+#[test]
+
+fn msm_chip_wrong_result_test() {
+    let (scalars, pts, mut instance) = msm_test_instance(4);
+    instance[1] = -instance[1];
+    let circuit = MsmTestCircuit::<3> {
+        scalars: scalars.into_iter().map(Value::known).collect(),
+        points: pts.into_iter().map(Value::known).collect(),
+    };
+    let prover = MockProver::run(11, &circuit, vec![instance]).unwrap();
+    assert!(prover.verify().is_err());
+}
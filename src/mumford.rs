@@ -0,0 +1,192 @@
+use crate::error::MsmWitnessError;
+use crate::regular_functions_utils::{
+    affine_coords, check_short_weierstrass, compute_divisor_witness, gen_random_pt, poly,
+    FftPrecomp, Grumpkin, Polynomial, RegularFunction,
+};
+use halo2curves::ff::{Field, PrimeField};
+use halo2curves::group::Group;
+use halo2curves::CurveExt;
+use rand_core::OsRng;
+
+/// Mumford representation (u, v) of an effective divisor sum P_i, whose points have distinct x coordinates:
+/// u = prod (x - x_i) is monic, and v is the polynomial of degree < deg u with v(x_i) = y_i.
+/// In particular u divides v^2 - (x^3 + ax + b). The identity doesn't show up in the representation.
+#[derive(Clone)]
+pub struct Mumford<C: CurveExt>
+where
+    C::Base: FftPrecomp,
+{
+    pub u: Polynomial<C::Base>,
+    pub v: Polynomial<C::Base>,
+}
+
+impl<C: CurveExt> Mumford<C>
+where
+    C::Base: FftPrecomp,
+{
+    /// Fails if two of the points (other than the identity) have the same x coordinate.
+    pub fn from_points(pts: &[C]) -> Result<Self, MsmWitnessError> {
+        check_short_weierstrass::<C>()?;
+        let coords: Vec<(C::Base, C::Base)> = pts.iter().filter_map(affine_coords).collect();
+        let xs: Vec<C::Base> = coords.iter().map(|(x, _)| *x).collect();
+        let u = Polynomial::from_roots(&xs);
+        // repeated roots of u are the common roots with its derivative
+        if u.gcd(&u.derivative()).degree() != Some(0) {
+            return Err(MsmWitnessError::DuplicateX);
+        }
+        Ok(Mumford {
+            u,
+            v: Polynomial::interpolate(&coords)?,
+        })
+    }
+
+    /// Recovers the points, fails if u doesn't split into distinct linear factors
+    /// or u doesn't divide v^2 - (x^3 + ax + b).
+    pub fn to_points(&self) -> Result<Vec<C>, MsmWitnessError> {
+        self.check()?;
+        let xs = distinct_roots(&self.u).ok_or(MsmWitnessError::InvalidMumford)?;
+        let ys = self.v.ev_many(&xs);
+        Ok(xs
+            .into_iter()
+            .zip(ys)
+            .map(|(x, y)| C::new_jacobian(x, y, C::Base::ONE).unwrap())
+            .collect())
+    }
+
+    fn check(&self) -> Result<(), MsmWitnessError> {
+        let curve = poly([C::b(), C::a(), C::Base::ZERO, C::Base::ONE]); // x^3 + ax + b
+        let monic = self.u.coeffs().last() == Some(&C::Base::ONE);
+        let reduced = self.v.degree() < self.u.degree();
+        if !monic || !reduced || !(&(&self.v * &self.v) - &curve).div_rem(&self.u).1.is_zero() {
+            return Err(MsmWitnessError::InvalidMumford);
+        }
+        Ok(())
+    }
+
+    /// Derives the function a(x) + y b(x) vanishing exactly on the divisor, which must be principal,
+    /// i.e. its points must sum to the identity. The function is only defined up to a constant factor,
+    /// this one is normalized, see RegularFunction::normalize.
+    ///
+    /// In the points of the divisor y = v(x), so we need a + v b = 0 mod u with deg a <= n/2 and
+    /// deg b <= (n-3)/2; such pair is found by the extended Euclidean algorithm on (u, v).
+    pub fn regular_function(&self) -> Result<RegularFunction<C>, MsmWitnessError> {
+        self.check()?;
+        let n = self.u.degree().unwrap();
+        if n == 0 {
+            return Ok(RegularFunction::from_const(C::Base::ONE));
+        }
+        let k = n / 2 + 1;
+
+        // invariant: r = s u + t v
+        let (mut r0, mut r1) = (self.u.clone(), self.v.clone());
+        let (mut t0, mut t1) = (Polynomial::new(vec![]), poly([C::Base::ONE]));
+        while r1.degree().map_or(false, |d| d >= k) {
+            let (q, r) = r0.div_rem(&r1);
+            let t = &t0 - &(&q * &t1);
+            (r0, r1) = (r1, r);
+            (t0, t1) = (t1, t);
+        }
+        let (a, b) = (r1, -t1);
+
+        // the function has a pole of order max(2 deg a, 2 deg b + 3) at infinity, and the same amount of zeros
+        let pole = [a.degree().map(|d| 2 * d), b.degree().map(|d| 2 * d + 3)]
+            .into_iter()
+            .flatten()
+            .max();
+        if pole != Some(n) {
+            return Err(MsmWitnessError::NotPrincipal);
+        }
+
+        Ok(RegularFunction::new(a, b).normalize())
+    }
+}
+
+/// Roots of a polynomial which is a product of distinct linear factors, None otherwise.
+/// Uses Cantor-Zassenhaus splitting: for random d, gcd(u, (x + d)^((p-1)/2) - 1) is a nontrivial factor
+/// with probability about 1/2.
+fn distinct_roots<F: PrimeField + FftPrecomp>(u: &Polynomial<F>) -> Option<Vec<F>> {
+    let x = poly([F::ZERO, F::ONE]);
+    // p - 1 and (p - 1) / 2 as little-endian bytes
+    let p_minus_1 = (-F::ONE).to_repr();
+    let p_minus_1 = p_minus_1.as_ref();
+    let half: Vec<u8> = (0..p_minus_1.len())
+        .map(|i| (p_minus_1[i] >> 1) | (p_minus_1.get(i + 1).map_or(0, |b| b << 7)))
+        .collect();
+
+    // u splits into distinct linear factors iff it divides x^p - x
+    let xp = &x.pow_mod(p_minus_1, u) * &x;
+    if !(&xp - &x).div_rem(u).1.is_zero() {
+        return None;
+    }
+
+    let mut ret = vec![];
+    let mut stack = vec![u.clone()];
+    while let Some(u) = stack.pop() {
+        match u.degree() {
+            None | Some(0) => (),
+            Some(1) => ret.push(-u.coeffs()[0] * u.coeffs()[1].invert().unwrap()),
+            Some(deg) => loop {
+                let shifted = poly([F::random(OsRng), F::ONE]);
+                let h = &shifted.pow_mod(&half, &u) - &poly([F::ONE]);
+                let g = u.gcd(&h);
+                let deg_g = g.degree().unwrap_or(0);
+                if deg_g > 0 && deg_g < deg {
+                    stack.push(u.div_rem(&g).0);
+                    stack.push(g);
+                    break;
+                }
+            },
+        }
+    }
+    Some(ret)
+}
+
+#[test]
+
+fn mumford_test() {
+    let pts: Vec<Grumpkin> = (0..20).map(|_| gen_random_pt()).collect();
+    let mumford = Mumford::from_points(&pts).unwrap();
+    assert_eq!(mumford.u.degree(), Some(20));
+
+    let mut recovered: Vec<_> = mumford
+        .to_points()
+        .unwrap()
+        .iter()
+        .map(|pt| affine_coords(pt).unwrap())
+        .collect();
+    let mut expected: Vec<_> = pts.iter().map(|pt| affine_coords(pt).unwrap()).collect();
+    recovered.sort_by_key(|(x, _)| x.to_repr());
+    expected.sort_by_key(|(x, _)| x.to_repr());
+    assert!(recovered == expected);
+
+    assert!(matches!(
+        Mumford::from_points(&[pts[0], -pts[0]]),
+        Err(MsmWitnessError::DuplicateX)
+    ));
+}
+
+#[test]
+
+fn mumford_regular_function_test() {
+    for n in [3, 4, 9, 40] {
+        let mut pts: Vec<Grumpkin> = (0..n - 1).map(|_| gen_random_pt()).collect();
+        pts.push(-pts.iter().fold(Grumpkin::identity(), |acc, x| acc + x));
+        let f = Mumford::from_points(&pts)
+            .unwrap()
+            .regular_function()
+            .unwrap();
+        let g = compute_divisor_witness(&pts).unwrap();
+        assert!(f.same_divisor(&g));
+        assert!(f
+            .ev_many(&pts)
+            .unwrap()
+            .into_iter()
+            .all(|x| x.is_zero_vartime()));
+    }
+
+    let pts: Vec<Grumpkin> = (0..5).map(|_| gen_random_pt()).collect();
+    assert!(matches!(
+        Mumford::from_points(&pts).unwrap().regular_function(),
+        Err(MsmWitnessError::NotPrincipal)
+    ));
+}
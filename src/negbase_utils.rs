@@ -1,3 +1,4 @@
+use crate::error::MsmWitnessError;
 use halo2curves::ff::{Field, PrimeField};
 use num_bigint::BigInt;
 use num_bigint::Sign;
@@ -5,17 +6,75 @@ use num_traits::{pow, Zero};
 use rand::random;
 use std::vec;
 
-pub fn range_check(x: &BigInt) {
+pub fn range_check(x: &BigInt) -> Result<(), MsmWitnessError> {
     let threshold = pow(BigInt::from_bytes_le(Sign::Plus, &[2]), 127);
-    assert!(x < &threshold);
-    assert!(x > &-threshold);
+    if x < &threshold && x > &-threshold {
+        Ok(())
+    } else {
+        Err(MsmWitnessError::ValueOutOfRange)
+    }
+}
+
+/// Set of digits used by the negbase decomposition. Digits are always stored as u8 from 0 to base-1,
+/// for the symmetric set the stored digit d stands for d - (base-1)/2, i.e. the digits are
+/// -(base-1)/2, ..., (base-1)/2 as in the paper (which uses base 3 and digits -1, 0, 1).
+/// Symmetric set requires an odd base.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DigitSet {
+    #[default]
+    Positive,
+    Symmetric,
+}
+
+impl DigitSet {
+    pub fn check_base(&self, base: u8) -> Result<(), MsmWitnessError> {
+        match self {
+            _ if base < 2 => Err(MsmWitnessError::InvalidBase { base }),
+            DigitSet::Symmetric if base % 2 == 0 => Err(MsmWitnessError::InvalidBase { base }),
+            _ => Ok(()),
+        }
+    }
+
+    /// stored digit representing 0
+    pub fn offset(&self, base: u8) -> u8 {
+        match self {
+            DigitSet::Positive => 0,
+            DigitSet::Symmetric => (base - 1) / 2,
+        }
+    }
+
+    /// value of the stored digit
+    pub fn value(&self, base: u8, digit: u8) -> i64 {
+        digit as i64 - self.offset(base) as i64
+    }
+
+    /// used for indexing of digits, will return None if digit represents 0
+    /// nonzero digits are indexed in increasing order, same as in precompute_multiplicities
+    pub fn id_by_digit(&self, base: u8, digit: u8) -> Option<usize> {
+        let offset = self.offset(base);
+        if digit == offset {
+            return None;
+        }
+        Some(if digit < offset {
+            digit as usize
+        } else {
+            (digit - 1) as usize
+        })
+    }
 }
 
-pub fn negbase_decompose(x: &BigInt, base: u8) -> Vec<u8> {
+/// Decomposes x in base -base, least significant digit first. Digits are stored as described in DigitSet.
+pub fn negbase_decompose(
+    x: &BigInt,
+    base: u8,
+    digit_set: DigitSet,
+) -> Result<Vec<u8>, MsmWitnessError> {
+    digit_set.check_base(base)?;
+    let offset = digit_set.offset(base);
     let mut x = x.clone();
     let mut acc = vec![];
     while x != BigInt::zero() {
-        let mut digit = x.clone() % base;
+        let mut digit = (x.clone() + offset) % base;
         match digit.sign() {
             // NICE % OPERATOR YOU HAVE THERE BIGINT LIBRARY!!!!!!
             Sign::Minus => digit += base,
@@ -24,10 +83,10 @@ pub fn negbase_decompose(x: &BigInt, base: u8) -> Vec<u8> {
         let mut tmp = digit.clone().to_u64_digits().1;
         tmp.push(0);
         acc.push(tmp[0] as u8);
-        x = -((x - digit) / base);
+        x = -((x - digit + offset) / base);
     }
 
-    acc
+    Ok(acc)
 }
 
 pub enum Entry {
@@ -36,7 +95,7 @@ pub enum Entry {
     Limb(i128, u32),
 }
 
-/// used for indexing of digits, will return None if digit is 0
+/// used for indexing of digits, will return None if digit is 0 (positive digit set)
 pub fn id_by_digit(digit: u8) -> Option<usize> {
     if digit == 0 {
         return None;
@@ -44,8 +103,10 @@ pub fn id_by_digit(digit: u8) -> Option<usize> {
     Some((digit - 1) as usize)
 }
 
-pub fn digit_by_id(id: usize) -> u8 {
-    (id + 1).try_into().unwrap()
+pub fn digit_by_id(id: usize) -> Result<u8, MsmWitnessError> {
+    (id + 1)
+        .try_into()
+        .map_err(|_| MsmWitnessError::ValueOutOfRange)
 }
 
 pub fn table_entry_by_id<F: PrimeField>(base: u8, id: usize) -> F {
@@ -78,9 +139,14 @@ pub fn prepare_scalar_witness(
     base: u8,
     num_digits: usize,
     logtable: usize,
-) -> Vec<Vec<Entry>> {
-    let digits = negbase_decompose(sc, base);
-    assert!(digits.len() <= num_digits);
+) -> Result<Vec<Vec<Entry>>, MsmWitnessError> {
+    let digits = negbase_decompose(sc, base, DigitSet::Positive)?;
+    if digits.len() > num_digits {
+        return Err(MsmWitnessError::TooManyDigits {
+            digits: digits.len(),
+            max: num_digits,
+        });
+    }
     let num_limbs = (num_digits + logtable - 1) / logtable;
 
     let mut ret = vec![];
@@ -120,7 +186,7 @@ pub fn prepare_scalar_witness(
         }
     }
 
-    ret_
+    Ok(ret_)
 }
 
 #[test]
@@ -128,7 +194,7 @@ pub fn prepare_scalar_witness(
 fn negbase_test() {
     let rnd: u32 = random();
     let rnd = BigInt::from_bytes_le(Sign::Plus, &rnd.to_le_bytes());
-    let mut tmp = negbase_decompose(&rnd, 17);
+    let mut tmp = negbase_decompose(&rnd, 17, DigitSet::Positive).unwrap();
     tmp.reverse();
     assert!(
         tmp.into_iter()
@@ -136,3 +202,43 @@ fn negbase_test() {
             == rnd
     );
 }
+
+#[test]
+
+fn negbase_errors_test() {
+    let x = BigInt::from(12345);
+    assert_eq!(
+        negbase_decompose(&x, 1, DigitSet::Positive),
+        Err(MsmWitnessError::InvalidBase { base: 1 })
+    );
+    assert_eq!(
+        negbase_decompose(&x, 4, DigitSet::Symmetric),
+        Err(MsmWitnessError::InvalidBase { base: 4 })
+    );
+    assert!(matches!(
+        prepare_scalar_witness(&x, 3, 2, 2),
+        Err(MsmWitnessError::TooManyDigits { max: 2, .. })
+    ));
+    assert_eq!(
+        range_check(&pow(BigInt::from(2), 127)),
+        Err(MsmWitnessError::ValueOutOfRange)
+    );
+}
+
+#[test]
+
+fn negbase_symmetric_test() {
+    for base in [3, 7, 17] {
+        let rnd: i32 = random();
+        let rnd = BigInt::from(rnd);
+        let digit_set = DigitSet::Symmetric;
+        let mut tmp = negbase_decompose(&rnd, base, digit_set).unwrap();
+        tmp.reverse();
+        assert!(
+            tmp.into_iter()
+                .fold(BigInt::zero(), |acc, x| acc * (-(base as i64))
+                    + digit_set.value(base, x))
+                == rnd
+        );
+    }
+}
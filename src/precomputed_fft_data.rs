@@ -1,216 +1,27 @@
-use halo2curves::{bn256::Fr as F, serde::SerdeObject};
-use crate::regular_functions_utils::FftPrecomp;
-impl FftPrecomp for F {
-    fn omega_pow(exp2: u32) -> F {
-        let tmp = match exp2 {
-            0=>[184, 254, 57, 182, 197, 199, 50, 150, 153, 242, 15, 13, 64, 227, 92, 152, 216, 236, 176, 1, 0, 136, 221, 178, 41, 206, 152, 109, 13, 7, 105, 29],
-            1=>[179, 125, 197, 14, 173, 64, 239, 225, 107, 183, 64, 238, 206, 194, 26, 205, 102, 209, 251, 77, 139, 207, 140, 28, 48, 123, 51, 236, 19, 136, 241, 16],
-            2=>[110, 197, 204, 247, 213, 197, 64, 118, 167, 242, 243, 216, 31, 243, 22, 231, 71, 255, 48, 244, 246, 158, 220, 118, 219, 56, 78, 54, 101, 244, 28, 34],
-            3=>[218, 29, 60, 203, 106, 63, 28, 185, 24, 160, 115, 84, 100, 164, 196, 158, 12, 223, 15, 107, 100, 93, 249, 233, 246, 46, 196, 200, 220, 186, 122, 47],
-            4=>[204, 6, 127, 137, 69, 153, 94, 237, 105, 248, 185, 199, 118, 209, 197, 99, 121, 185, 130, 210, 71, 157, 68, 22, 220, 85, 205, 93, 127, 135, 42, 35],
-            5=>[239, 104, 196, 106, 188, 206, 199, 176, 111, 73, 175, 39, 246, 179, 126, 30, 17, 170, 84, 205, 204, 163, 146, 74, 95, 184, 195, 174, 109, 29, 71, 20],
-            6=>[100, 139, 58, 204, 73, 168, 237, 69, 144, 110, 165, 71, 244, 186, 90, 125, 43, 91, 195, 175, 239, 225, 70, 191, 62, 72, 56, 238, 163, 253, 253, 23],
-            7=>[125, 17, 250, 74, 221, 100, 7, 98, 87, 147, 45, 139, 113, 172, 28, 145, 186, 248, 86, 24, 33, 42, 119, 8, 193, 96, 245, 161, 85, 245, 104, 23],
-            8=>[51, 71, 158, 22, 68, 179, 235, 1, 32, 248, 181, 137, 106, 221, 118, 137, 221, 52, 14, 76, 3, 251, 159, 162, 0, 226, 97, 156, 21, 172, 179, 25],
-            9=>[206, 45, 126, 0, 115, 177, 117, 36, 234, 121, 5, 100, 95, 170, 45, 98, 220, 103, 241, 238, 215, 118, 174, 245, 127, 41, 106, 136, 177, 16, 238, 37],
-            10=>[231, 35, 234, 172, 184, 200, 230, 47, 241, 30, 141, 143, 5, 181, 206, 247, 240, 205, 18, 124, 31, 132, 39, 81, 48, 168, 185, 183, 125, 111, 34, 33],
-            11=>[224, 67, 225, 128, 230, 247, 87, 143, 123, 219, 74, 197, 249, 46, 138, 43, 147, 223, 107, 59, 143, 227, 16, 202, 0, 2, 93, 103, 94, 183, 45, 41],
-            12=>[16, 184, 211, 81, 146, 180, 1, 148, 156, 192, 143, 52, 245, 152, 60, 222, 131, 147, 212, 7, 139, 25, 181, 207, 99, 12, 127, 70, 91, 206, 236, 41],
-            13=>[212, 114, 14, 130, 76, 100, 105, 109, 12, 194, 114, 115, 200, 61, 99, 48, 157, 155, 90, 66, 65, 12, 59, 87, 23, 39, 10, 160, 127, 66, 88, 30],
-            14=>[17, 150, 7, 254, 144, 111, 19, 42, 50, 113, 140, 90, 59, 149, 145, 144, 55, 80, 150, 55, 55, 242, 223, 205, 38, 148, 20, 2, 94, 161, 41, 12],
-            15=>[26, 102, 25, 8, 137, 110, 232, 152, 148, 52, 61, 180, 194, 113, 137, 165, 12, 175, 110, 65, 77, 125, 203, 216, 39, 89, 239, 124, 34, 212, 4, 1],
-            16=>[217, 1, 62, 86, 103, 65, 164, 163, 37, 186, 12, 82, 186, 94, 152, 44, 33, 109, 112, 159, 146, 224, 11, 99, 46, 229, 141, 52, 99, 173, 140, 15],
-            17=>[46, 118, 0, 93, 252, 233, 81, 73, 229, 174, 100, 193, 130, 173, 125, 118, 242, 34, 78, 66, 241, 175, 225, 95, 151, 19, 176, 248, 237, 235, 101, 35],
-            18=>[19, 165, 32, 102, 117, 249, 89, 157, 153, 87, 1, 234, 163, 69, 231, 51, 108, 221, 191, 96, 191, 226, 150, 137, 199, 226, 53, 183, 2, 239, 166, 34],
-            19=>[51, 4, 9, 146, 143, 146, 200, 201, 163, 244, 217, 127, 166, 1, 71, 217, 139, 39, 143, 253, 251, 85, 230, 206, 222, 14, 45, 23, 112, 69, 142, 21],
-            20=>[0, 156, 163, 158, 49, 147, 143, 127, 130, 87, 204, 249, 89, 16, 37, 123, 124, 83, 251, 205, 103, 189, 131, 86, 172, 155, 168, 24, 174, 198, 236, 23],
-            21=>[128, 83, 93, 177, 6, 13, 20, 171, 151, 91, 49, 188, 204, 58, 47, 140, 79, 153, 4, 146, 37, 55, 89, 120, 52, 38, 226, 89, 240, 243, 178, 28],
-            22=>[66, 158, 112, 110, 174, 241, 81, 181, 206, 113, 192, 221, 19, 41, 152, 155, 14, 5, 128, 66, 233, 86, 115, 100, 237, 245, 7, 252, 6, 184, 211, 9],
-            23=>[100, 76, 87, 191, 177, 247, 20, 34, 242, 125, 49, 247, 47, 35, 249, 40, 205, 117, 173, 176, 168, 132, 117, 229, 3, 109, 23, 220, 89, 251, 129, 43],
-            24=>[3, 143, 47, 116, 124, 125, 182, 244, 204, 104, 208, 99, 220, 45, 27, 104, 106, 87, 251, 27, 239, 188, 229, 140, 254, 60, 182, 210, 81, 41, 124, 22],
-            25=>[63, 124, 173, 181, 226, 74, 173, 248, 190, 133, 203, 131, 255, 198, 96, 45, 247, 41, 148, 93, 43, 253, 118, 217, 169, 217, 154, 63, 231, 124, 64, 36],
-            26=>[139, 239, 220, 158, 151, 61, 117, 127, 32, 145, 71, 177, 44, 23, 63, 95, 110, 108, 9, 116, 121, 98, 177, 141, 207, 8, 193, 57, 53, 123, 55, 43],
-            27=>[6, 0, 0, 160, 119, 193, 75, 151, 103, 163, 88, 218, 178, 113, 55, 241, 46, 18, 8, 9, 71, 162, 225, 81, 250, 192, 41, 71, 177, 214, 89, 34],
-            28=>[251, 255, 255, 79, 28, 52, 150, 172, 41, 205, 96, 159, 149, 118, 252, 54, 46, 70, 121, 120, 111, 163, 110, 102, 47, 223, 7, 154, 193, 119, 10, 14],
-            29=>[251, 255, 255, 79, 28, 52, 150, 172, 41, 205, 96, 159, 149, 118, 252, 54, 46, 70, 121, 120, 111, 163, 110, 102, 47, 223, 7, 154, 193, 119, 10, 14],
-            30=>[251, 255, 255, 79, 28, 52, 150, 172, 41, 205, 96, 159, 149, 118, 252, 54, 46, 70, 121, 120, 111, 163, 110, 102, 47, 223, 7, 154, 193, 119, 10, 14],
-            31=>[251, 255, 255, 79, 28, 52, 150, 172, 41, 205, 96, 159, 149, 118, 252, 54, 46, 70, 121, 120, 111, 163, 110, 102, 47, 223, 7, 154, 193, 119, 10, 14],
-            32=>[251, 255, 255, 79, 28, 52, 150, 172, 41, 205, 96, 159, 149, 118, 252, 54, 46, 70, 121, 120, 111, 163, 110, 102, 47, 223, 7, 154, 193, 119, 10, 14],
-            33=>[251, 255, 255, 79, 28, 52, 150, 172, 41, 205, 96, 159, 149, 118, 252, 54, 46, 70, 121, 120, 111, 163, 110, 102, 47, 223, 7, 154, 193, 119, 10, 14],
-            34=>[251, 255, 255, 79, 28, 52, 150, 172, 41, 205, 96, 159, 149, 118, 252, 54, 46, 70, 121, 120, 111, 163, 110, 102, 47, 223, 7, 154, 193, 119, 10, 14],
-            35=>[251, 255, 255, 79, 28, 52, 150, 172, 41, 205, 96, 159, 149, 118, 252, 54, 46, 70, 121, 120, 111, 163, 110, 102, 47, 223, 7, 154, 193, 119, 10, 14],
-            36=>[251, 255, 255, 79, 28, 52, 150, 172, 41, 205, 96, 159, 149, 118, 252, 54, 46, 70, 121, 120, 111, 163, 110, 102, 47, 223, 7, 154, 193, 119, 10, 14],
-            37=>[251, 255, 255, 79, 28, 52, 150, 172, 41, 205, 96, 159, 149, 118, 252, 54, 46, 70, 121, 120, 111, 163, 110, 102, 47, 223, 7, 154, 193, 119, 10, 14],
-            38=>[251, 255, 255, 79, 28, 52, 150, 172, 41, 205, 96, 159, 149, 118, 252, 54, 46, 70, 121, 120, 111, 163, 110, 102, 47, 223, 7, 154, 193, 119, 10, 14],
-            39=>[251, 255, 255, 79, 28, 52, 150, 172, 41, 205, 96, 159, 149, 118, 252, 54, 46, 70, 121, 120, 111, 163, 110, 102, 47, 223, 7, 154, 193, 119, 10, 14],
-            40=>[251, 255, 255, 79, 28, 52, 150, 172, 41, 205, 96, 159, 149, 118, 252, 54, 46, 70, 121, 120, 111, 163, 110, 102, 47, 223, 7, 154, 193, 119, 10, 14],
-            41=>[251, 255, 255, 79, 28, 52, 150, 172, 41, 205, 96, 159, 149, 118, 252, 54, 46, 70, 121, 120, 111, 163, 110, 102, 47, 223, 7, 154, 193, 119, 10, 14],
-            42=>[251, 255, 255, 79, 28, 52, 150, 172, 41, 205, 96, 159, 149, 118, 252, 54, 46, 70, 121, 120, 111, 163, 110, 102, 47, 223, 7, 154, 193, 119, 10, 14],
-            43=>[251, 255, 255, 79, 28, 52, 150, 172, 41, 205, 96, 159, 149, 118, 252, 54, 46, 70, 121, 120, 111, 163, 110, 102, 47, 223, 7, 154, 193, 119, 10, 14],
-            44=>[251, 255, 255, 79, 28, 52, 150, 172, 41, 205, 96, 159, 149, 118, 252, 54, 46, 70, 121, 120, 111, 163, 110, 102, 47, 223, 7, 154, 193, 119, 10, 14],
-            45=>[251, 255, 255, 79, 28, 52, 150, 172, 41, 205, 96, 159, 149, 118, 252, 54, 46, 70, 121, 120, 111, 163, 110, 102, 47, 223, 7, 154, 193, 119, 10, 14],
-            46=>[251, 255, 255, 79, 28, 52, 150, 172, 41, 205, 96, 159, 149, 118, 252, 54, 46, 70, 121, 120, 111, 163, 110, 102, 47, 223, 7, 154, 193, 119, 10, 14],
-            47=>[251, 255, 255, 79, 28, 52, 150, 172, 41, 205, 96, 159, 149, 118, 252, 54, 46, 70, 121, 120, 111, 163, 110, 102, 47, 223, 7, 154, 193, 119, 10, 14],
-            48=>[251, 255, 255, 79, 28, 52, 150, 172, 41, 205, 96, 159, 149, 118, 252, 54, 46, 70, 121, 120, 111, 163, 110, 102, 47, 223, 7, 154, 193, 119, 10, 14],
-            49=>[251, 255, 255, 79, 28, 52, 150, 172, 41, 205, 96, 159, 149, 118, 252, 54, 46, 70, 121, 120, 111, 163, 110, 102, 47, 223, 7, 154, 193, 119, 10, 14],
-            50=>[251, 255, 255, 79, 28, 52, 150, 172, 41, 205, 96, 159, 149, 118, 252, 54, 46, 70, 121, 120, 111, 163, 110, 102, 47, 223, 7, 154, 193, 119, 10, 14],
-            51=>[251, 255, 255, 79, 28, 52, 150, 172, 41, 205, 96, 159, 149, 118, 252, 54, 46, 70, 121, 120, 111, 163, 110, 102, 47, 223, 7, 154, 193, 119, 10, 14],
-            52=>[251, 255, 255, 79, 28, 52, 150, 172, 41, 205, 96, 159, 149, 118, 252, 54, 46, 70, 121, 120, 111, 163, 110, 102, 47, 223, 7, 154, 193, 119, 10, 14],
-            53=>[251, 255, 255, 79, 28, 52, 150, 172, 41, 205, 96, 159, 149, 118, 252, 54, 46, 70, 121, 120, 111, 163, 110, 102, 47, 223, 7, 154, 193, 119, 10, 14],
-            54=>[251, 255, 255, 79, 28, 52, 150, 172, 41, 205, 96, 159, 149, 118, 252, 54, 46, 70, 121, 120, 111, 163, 110, 102, 47, 223, 7, 154, 193, 119, 10, 14],
-            55=>[251, 255, 255, 79, 28, 52, 150, 172, 41, 205, 96, 159, 149, 118, 252, 54, 46, 70, 121, 120, 111, 163, 110, 102, 47, 223, 7, 154, 193, 119, 10, 14],
-            56=>[251, 255, 255, 79, 28, 52, 150, 172, 41, 205, 96, 159, 149, 118, 252, 54, 46, 70, 121, 120, 111, 163, 110, 102, 47, 223, 7, 154, 193, 119, 10, 14],
-            57=>[251, 255, 255, 79, 28, 52, 150, 172, 41, 205, 96, 159, 149, 118, 252, 54, 46, 70, 121, 120, 111, 163, 110, 102, 47, 223, 7, 154, 193, 119, 10, 14],
-            58=>[251, 255, 255, 79, 28, 52, 150, 172, 41, 205, 96, 159, 149, 118, 252, 54, 46, 70, 121, 120, 111, 163, 110, 102, 47, 223, 7, 154, 193, 119, 10, 14],
-            59=>[251, 255, 255, 79, 28, 52, 150, 172, 41, 205, 96, 159, 149, 118, 252, 54, 46, 70, 121, 120, 111, 163, 110, 102, 47, 223, 7, 154, 193, 119, 10, 14],
-            60=>[251, 255, 255, 79, 28, 52, 150, 172, 41, 205, 96, 159, 149, 118, 252, 54, 46, 70, 121, 120, 111, 163, 110, 102, 47, 223, 7, 154, 193, 119, 10, 14],
-            61=>[251, 255, 255, 79, 28, 52, 150, 172, 41, 205, 96, 159, 149, 118, 252, 54, 46, 70, 121, 120, 111, 163, 110, 102, 47, 223, 7, 154, 193, 119, 10, 14],
-            62=>[251, 255, 255, 79, 28, 52, 150, 172, 41, 205, 96, 159, 149, 118, 252, 54, 46, 70, 121, 120, 111, 163, 110, 102, 47, 223, 7, 154, 193, 119, 10, 14],
-            63=>[251, 255, 255, 79, 28, 52, 150, 172, 41, 205, 96, 159, 149, 118, 252, 54, 46, 70, 121, 120, 111, 163, 110, 102, 47, 223, 7, 154, 193, 119, 10, 14],
-            _=>panic!(),
-        };
-    F::from_raw_bytes_unchecked(&tmp)
-    }
+// FftPrecomp implementations with hardcoded tables, generated by build.rs
+include!(concat!(env!("OUT_DIR"), "/precomputed_fft_data.rs"));
 
-    fn omega_pow_inv(exp2: u32) -> F {
-        let tmp = match exp2 {
-            0=>[150, 61, 251, 175, 5, 92, 240, 5, 55, 81, 59, 252, 235, 148, 229, 184, 193, 196, 91, 184, 32, 70, 49, 96, 145, 197, 111, 187, 190, 41, 65, 42],
-            1=>[231, 21, 172, 199, 112, 122, 51, 2, 162, 187, 122, 98, 181, 4, 26, 127, 6, 138, 77, 80, 77, 140, 72, 145, 117, 245, 139, 93, 60, 210, 132, 45],
-            2=>[209, 177, 154, 30, 22, 60, 64, 36, 190, 124, 114, 89, 141, 49, 26, 139, 73, 150, 18, 225, 54, 203, 35, 222, 114, 197, 171, 150, 225, 200, 163, 1],
-            3=>[64, 62, 119, 159, 123, 49, 0, 38, 28, 45, 133, 163, 147, 43, 165, 121, 128, 51, 66, 191, 189, 64, 30, 92, 12, 43, 144, 51, 89, 44, 126, 17],
-            4=>[255, 196, 165, 113, 227, 51, 172, 94, 17, 16, 139, 171, 121, 200, 155, 108, 212, 195, 4, 41, 173, 56, 214, 195, 169, 89, 229, 250, 236, 145, 20, 14],
-            5=>[31, 119, 199, 91, 70, 213, 126, 49, 141, 189, 23, 38, 145, 194, 198, 154, 20, 46, 241, 197, 167, 34, 178, 156, 130, 102, 188, 118, 24, 248, 51, 11],
-            6=>[34, 142, 112, 44, 87, 70, 222, 53, 61, 246, 75, 186, 47, 151, 127, 34, 1, 245, 193, 128, 225, 213, 152, 175, 151, 238, 120, 148, 31, 117, 98, 8],
-            7=>[192, 189, 148, 17, 84, 34, 247, 102, 223, 3, 59, 69, 140, 218, 22, 63, 230, 17, 49, 79, 195, 90, 254, 236, 42, 35, 246, 87, 130, 229, 179, 32],
-            8=>[251, 97, 128, 24, 53, 148, 43, 126, 202, 209, 179, 208, 147, 247, 252, 180, 158, 158, 15, 131, 168, 151, 16, 22, 100, 217, 14, 100, 14, 113, 105, 17],
-            9=>[98, 216, 18, 245, 3, 147, 81, 162, 219, 177, 255, 134, 105, 122, 13, 159, 150, 118, 185, 225, 223, 22, 61, 44, 151, 24, 240, 95, 121, 148, 125, 26],
-            10=>[15, 139, 3, 52, 167, 207, 128, 183, 237, 77, 212, 178, 37, 62, 80, 36, 110, 99, 97, 119, 177, 37, 172, 51, 189, 100, 196, 235, 145, 240, 184, 40],
-            11=>[8, 208, 155, 234, 88, 144, 197, 146, 46, 46, 200, 1, 105, 12, 215, 134, 212, 10, 153, 124, 172, 56, 102, 101, 17, 244, 21, 219, 173, 167, 48, 48],
-            12=>[108, 210, 51, 204, 202, 78, 5, 215, 17, 193, 191, 147, 143, 116, 179, 212, 220, 248, 59, 248, 22, 19, 209, 62, 105, 129, 255, 33, 83, 181, 41, 28],
-            13=>[183, 22, 124, 181, 5, 79, 24, 235, 227, 30, 61, 168, 216, 210, 206, 30, 102, 56, 199, 104, 158, 23, 41, 13, 86, 117, 139, 198, 120, 3, 134, 39],
-            14=>[82, 252, 52, 91, 138, 94, 163, 107, 84, 173, 56, 23, 47, 217, 238, 199, 86, 86, 245, 77, 158, 253, 55, 27, 248, 106, 54, 161, 213, 27, 198, 8],
-            15=>[186, 129, 146, 114, 62, 65, 35, 34, 69, 113, 248, 134, 93, 162, 96, 103, 152, 121, 108, 91, 143, 2, 119, 101, 98, 212, 171, 206, 87, 164, 167, 31],
-            16=>[180, 136, 41, 55, 44, 160, 112, 250, 143, 190, 163, 134, 120, 124, 187, 242, 227, 159, 116, 158, 125, 199, 90, 70, 222, 10, 205, 248, 169, 75, 229, 30],
-            17=>[232, 211, 213, 61, 64, 156, 158, 176, 238, 226, 17, 90, 61, 63, 28, 114, 211, 206, 172, 27, 132, 157, 174, 237, 51, 204, 255, 208, 126, 28, 160, 17],
-            18=>[97, 234, 63, 59, 124, 23, 194, 7, 98, 119, 27, 46, 180, 16, 63, 168, 190, 54, 185, 222, 213, 130, 239, 40, 187, 242, 36, 145, 148, 162, 35, 4],
-            19=>[70, 180, 181, 57, 98, 104, 108, 210, 156, 108, 64, 90, 221, 111, 250, 227, 25, 208, 186, 46, 224, 117, 252, 66, 76, 249, 215, 83, 83, 34, 207, 9],
-            20=>[101, 55, 65, 238, 53, 75, 250, 217, 131, 232, 97, 52, 131, 51, 172, 8, 249, 37, 209, 100, 59, 40, 48, 66, 49, 136, 122, 190, 158, 159, 213, 30],
-            21=>[53, 188, 97, 114, 155, 63, 63, 30, 145, 155, 251, 121, 182, 143, 151, 9, 75, 108, 134, 37, 239, 104, 192, 190, 233, 187, 11, 53, 18, 78, 31, 32],
-            22=>[125, 135, 138, 42, 153, 30, 33, 178, 55, 43, 250, 146, 172, 98, 177, 128, 143, 97, 249, 199, 200, 48, 130, 244, 193, 128, 216, 115, 173, 133, 122, 36],
-            23=>[180, 207, 31, 0, 17, 231, 58, 40, 42, 243, 180, 254, 83, 210, 234, 5, 202, 68, 17, 250, 171, 56, 195, 203, 142, 252, 120, 219, 16, 76, 14, 48],
-            24=>[14, 182, 210, 165, 135, 167, 47, 48, 183, 54, 64, 181, 79, 145, 204, 128, 59, 235, 53, 210, 179, 192, 101, 79, 94, 161, 8, 81, 8, 18, 219, 10],
-            25=>[155, 158, 162, 221, 221, 203, 7, 50, 252, 190, 47, 38, 166, 53, 202, 119, 56, 222, 89, 176, 223, 7, 126, 61, 87, 17, 189, 50, 95, 29, 169, 18],
-            26=>[118, 16, 35, 81, 252, 183, 108, 196, 112, 223, 113, 200, 27, 209, 244, 200, 238, 235, 119, 13, 61, 227, 158, 42, 90, 151, 112, 167, 61, 211, 44, 5],
-            27=>[6, 0, 0, 160, 119, 193, 75, 151, 103, 163, 88, 218, 178, 113, 55, 241, 46, 18, 8, 9, 71, 162, 225, 81, 250, 192, 41, 71, 177, 214, 89, 34],
-            28=>[251, 255, 255, 79, 28, 52, 150, 172, 41, 205, 96, 159, 149, 118, 252, 54, 46, 70, 121, 120, 111, 163, 110, 102, 47, 223, 7, 154, 193, 119, 10, 14],
-            29=>[251, 255, 255, 79, 28, 52, 150, 172, 41, 205, 96, 159, 149, 118, 252, 54, 46, 70, 121, 120, 111, 163, 110, 102, 47, 223, 7, 154, 193, 119, 10, 14],
-            30=>[251, 255, 255, 79, 28, 52, 150, 172, 41, 205, 96, 159, 149, 118, 252, 54, 46, 70, 121, 120, 111, 163, 110, 102, 47, 223, 7, 154, 193, 119, 10, 14],
-            31=>[251, 255, 255, 79, 28, 52, 150, 172, 41, 205, 96, 159, 149, 118, 252, 54, 46, 70, 121, 120, 111, 163, 110, 102, 47, 223, 7, 154, 193, 119, 10, 14],
-            32=>[251, 255, 255, 79, 28, 52, 150, 172, 41, 205, 96, 159, 149, 118, 252, 54, 46, 70, 121, 120, 111, 163, 110, 102, 47, 223, 7, 154, 193, 119, 10, 14],
-            33=>[251, 255, 255, 79, 28, 52, 150, 172, 41, 205, 96, 159, 149, 118, 252, 54, 46, 70, 121, 120, 111, 163, 110, 102, 47, 223, 7, 154, 193, 119, 10, 14],
-            34=>[251, 255, 255, 79, 28, 52, 150, 172, 41, 205, 96, 159, 149, 118, 252, 54, 46, 70, 121, 120, 111, 163, 110, 102, 47, 223, 7, 154, 193, 119, 10, 14],
-            35=>[251, 255, 255, 79, 28, 52, 150, 172, 41, 205, 96, 159, 149, 118, 252, 54, 46, 70, 121, 120, 111, 163, 110, 102, 47, 223, 7, 154, 193, 119, 10, 14],
-            36=>[251, 255, 255, 79, 28, 52, 150, 172, 41, 205, 96, 159, 149, 118, 252, 54, 46, 70, 121, 120, 111, 163, 110, 102, 47, 223, 7, 154, 193, 119, 10, 14],
-            37=>[251, 255, 255, 79, 28, 52, 150, 172, 41, 205, 96, 159, 149, 118, 252, 54, 46, 70, 121, 120, 111, 163, 110, 102, 47, 223, 7, 154, 193, 119, 10, 14],
-            38=>[251, 255, 255, 79, 28, 52, 150, 172, 41, 205, 96, 159, 149, 118, 252, 54, 46, 70, 121, 120, 111, 163, 110, 102, 47, 223, 7, 154, 193, 119, 10, 14],
-            39=>[251, 255, 255, 79, 28, 52, 150, 172, 41, 205, 96, 159, 149, 118, 252, 54, 46, 70, 121, 120, 111, 163, 110, 102, 47, 223, 7, 154, 193, 119, 10, 14],
-            40=>[251, 255, 255, 79, 28, 52, 150, 172, 41, 205, 96, 159, 149, 118, 252, 54, 46, 70, 121, 120, 111, 163, 110, 102, 47, 223, 7, 154, 193, 119, 10, 14],
-            41=>[251, 255, 255, 79, 28, 52, 150, 172, 41, 205, 96, 159, 149, 118, 252, 54, 46, 70, 121, 120, 111, 163, 110, 102, 47, 223, 7, 154, 193, 119, 10, 14],
-            42=>[251, 255, 255, 79, 28, 52, 150, 172, 41, 205, 96, 159, 149, 118, 252, 54, 46, 70, 121, 120, 111, 163, 110, 102, 47, 223, 7, 154, 193, 119, 10, 14],
-            43=>[251, 255, 255, 79, 28, 52, 150, 172, 41, 205, 96, 159, 149, 118, 252, 54, 46, 70, 121, 120, 111, 163, 110, 102, 47, 223, 7, 154, 193, 119, 10, 14],
-            44=>[251, 255, 255, 79, 28, 52, 150, 172, 41, 205, 96, 159, 149, 118, 252, 54, 46, 70, 121, 120, 111, 163, 110, 102, 47, 223, 7, 154, 193, 119, 10, 14],
-            45=>[251, 255, 255, 79, 28, 52, 150, 172, 41, 205, 96, 159, 149, 118, 252, 54, 46, 70, 121, 120, 111, 163, 110, 102, 47, 223, 7, 154, 193, 119, 10, 14],
-            46=>[251, 255, 255, 79, 28, 52, 150, 172, 41, 205, 96, 159, 149, 118, 252, 54, 46, 70, 121, 120, 111, 163, 110, 102, 47, 223, 7, 154, 193, 119, 10, 14],
-            47=>[251, 255, 255, 79, 28, 52, 150, 172, 41, 205, 96, 159, 149, 118, 252, 54, 46, 70, 121, 120, 111, 163, 110, 102, 47, 223, 7, 154, 193, 119, 10, 14],
-            48=>[251, 255, 255, 79, 28, 52, 150, 172, 41, 205, 96, 159, 149, 118, 252, 54, 46, 70, 121, 120, 111, 163, 110, 102, 47, 223, 7, 154, 193, 119, 10, 14],
-            49=>[251, 255, 255, 79, 28, 52, 150, 172, 41, 205, 96, 159, 149, 118, 252, 54, 46, 70, 121, 120, 111, 163, 110, 102, 47, 223, 7, 154, 193, 119, 10, 14],
-            50=>[251, 255, 255, 79, 28, 52, 150, 172, 41, 205, 96, 159, 149, 118, 252, 54, 46, 70, 121, 120, 111, 163, 110, 102, 47, 223, 7, 154, 193, 119, 10, 14],
-            51=>[251, 255, 255, 79, 28, 52, 150, 172, 41, 205, 96, 159, 149, 118, 252, 54, 46, 70, 121, 120, 111, 163, 110, 102, 47, 223, 7, 154, 193, 119, 10, 14],
-            52=>[251, 255, 255, 79, 28, 52, 150, 172, 41, 205, 96, 159, 149, 118, 252, 54, 46, 70, 121, 120, 111, 163, 110, 102, 47, 223, 7, 154, 193, 119, 10, 14],
-            53=>[251, 255, 255, 79, 28, 52, 150, 172, 41, 205, 96, 159, 149, 118, 252, 54, 46, 70, 121, 120, 111, 163, 110, 102, 47, 223, 7, 154, 193, 119, 10, 14],
-            54=>[251, 255, 255, 79, 28, 52, 150, 172, 41, 205, 96, 159, 149, 118, 252, 54, 46, 70, 121, 120, 111, 163, 110, 102, 47, 223, 7, 154, 193, 119, 10, 14],
-            55=>[251, 255, 255, 79, 28, 52, 150, 172, 41, 205, 96, 159, 149, 118, 252, 54, 46, 70, 121, 120, 111, 163, 110, 102, 47, 223, 7, 154, 193, 119, 10, 14],
-            56=>[251, 255, 255, 79, 28, 52, 150, 172, 41, 205, 96, 159, 149, 118, 252, 54, 46, 70, 121, 120, 111, 163, 110, 102, 47, 223, 7, 154, 193, 119, 10, 14],
-            57=>[251, 255, 255, 79, 28, 52, 150, 172, 41, 205, 96, 159, 149, 118, 252, 54, 46, 70, 121, 120, 111, 163, 110, 102, 47, 223, 7, 154, 193, 119, 10, 14],
-            58=>[251, 255, 255, 79, 28, 52, 150, 172, 41, 205, 96, 159, 149, 118, 252, 54, 46, 70, 121, 120, 111, 163, 110, 102, 47, 223, 7, 154, 193, 119, 10, 14],
-            59=>[251, 255, 255, 79, 28, 52, 150, 172, 41, 205, 96, 159, 149, 118, 252, 54, 46, 70, 121, 120, 111, 163, 110, 102, 47, 223, 7, 154, 193, 119, 10, 14],
-            60=>[251, 255, 255, 79, 28, 52, 150, 172, 41, 205, 96, 159, 149, 118, 252, 54, 46, 70, 121, 120, 111, 163, 110, 102, 47, 223, 7, 154, 193, 119, 10, 14],
-            61=>[251, 255, 255, 79, 28, 52, 150, 172, 41, 205, 96, 159, 149, 118, 252, 54, 46, 70, 121, 120, 111, 163, 110, 102, 47, 223, 7, 154, 193, 119, 10, 14],
-            62=>[251, 255, 255, 79, 28, 52, 150, 172, 41, 205, 96, 159, 149, 118, 252, 54, 46, 70, 121, 120, 111, 163, 110, 102, 47, 223, 7, 154, 193, 119, 10, 14],
-            63=>[251, 255, 255, 79, 28, 52, 150, 172, 41, 205, 96, 159, 149, 118, 252, 54, 46, 70, 121, 120, 111, 163, 110, 102, 47, 223, 7, 154, 193, 119, 10, 14],
-            _=>panic!(),
-        };
-    F::from_raw_bytes_unchecked(&tmp)
-    }
+#[test]
 
-    fn half_pow(exp: u64) -> F {
-        let tmp = match exp {
-            0=>[251, 255, 255, 79, 28, 52, 150, 172, 41, 205, 96, 159, 149, 118, 252, 54, 46, 70, 121, 120, 111, 163, 110, 102, 47, 223, 7, 154, 193, 119, 10, 14],
-            1=>[254, 255, 255, 31, 216, 20, 60, 120, 221, 30, 141, 12, 111, 47, 152, 175, 69, 79, 253, 252, 146, 116, 95, 143, 172, 191, 156, 61, 26, 99, 55, 31],
-            2=>[255, 255, 255, 15, 108, 10, 30, 188, 110, 143, 70, 134, 183, 23, 204, 215, 162, 167, 126, 126, 73, 186, 175, 71, 214, 95, 206, 30, 141, 177, 155, 15],
-            3=>[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 32],
-            4=>[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 16],
-            5=>[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 8],
-            6=>[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 4],
-            7=>[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 2],
-            8=>[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1],
-            9=>[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 128, 0],
-            10=>[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 64, 0],
-            11=>[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 32, 0],
-            12=>[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 16, 0],
-            13=>[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 8, 0],
-            14=>[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 4, 0],
-            15=>[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 2, 0],
-            16=>[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0],
-            17=>[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 128, 0, 0],
-            18=>[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 64, 0, 0],
-            19=>[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 32, 0, 0],
-            20=>[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 16, 0, 0],
-            21=>[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 8, 0, 0],
-            22=>[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 4, 0, 0],
-            23=>[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 2, 0, 0],
-            24=>[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0],
-            25=>[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 128, 0, 0, 0],
-            26=>[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 64, 0, 0, 0],
-            27=>[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 32, 0, 0, 0],
-            28=>[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 16, 0, 0, 0],
-            29=>[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 8, 0, 0, 0],
-            30=>[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 4, 0, 0, 0],
-            31=>[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 2, 0, 0, 0],
-            32=>[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0],
-            33=>[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 128, 0, 0, 0, 0],
-            34=>[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 64, 0, 0, 0, 0],
-            35=>[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 32, 0, 0, 0, 0],
-            36=>[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 16, 0, 0, 0, 0],
-            37=>[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 8, 0, 0, 0, 0],
-            38=>[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 4, 0, 0, 0, 0],
-            39=>[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 2, 0, 0, 0, 0],
-            40=>[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0],
-            41=>[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 128, 0, 0, 0, 0, 0],
-            42=>[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 64, 0, 0, 0, 0, 0],
-            43=>[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 32, 0, 0, 0, 0, 0],
-            44=>[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 16, 0, 0, 0, 0, 0],
-            45=>[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 8, 0, 0, 0, 0, 0],
-            46=>[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 4, 0, 0, 0, 0, 0],
-            47=>[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 2, 0, 0, 0, 0, 0],
-            48=>[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0],
-            49=>[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 128, 0, 0, 0, 0, 0, 0],
-            50=>[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 64, 0, 0, 0, 0, 0, 0],
-            51=>[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 32, 0, 0, 0, 0, 0, 0],
-            52=>[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 16, 0, 0, 0, 0, 0, 0],
-            53=>[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 8, 0, 0, 0, 0, 0, 0],
-            54=>[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 4, 0, 0, 0, 0, 0, 0],
-            55=>[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 2, 0, 0, 0, 0, 0, 0],
-            56=>[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0],
-            57=>[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 128, 0, 0, 0, 0, 0, 0, 0],
-            58=>[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 64, 0, 0, 0, 0, 0, 0, 0],
-            59=>[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 32, 0, 0, 0, 0, 0, 0, 0],
-            60=>[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 16, 0, 0, 0, 0, 0, 0, 0],
-            61=>[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 8, 0, 0, 0, 0, 0, 0, 0],
-            62=>[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 4, 0, 0, 0, 0, 0, 0, 0],
-            63=>[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 2, 0, 0, 0, 0, 0, 0, 0],
-            _=>panic!(),
-        };
-    F::from_raw_bytes_unchecked(&tmp)
+fn precomputed_fft_data_test() {
+    use crate::regular_functions_utils::FftPrecomp;
+    use halo2curves::bn256::Fr as F;
+    use halo2curves::ff::{Field, PrimeField};
+
+    for i in 0..=F::S {
+        assert!(F::omega_pow(i) == F::ROOT_OF_UNITY.pow([1u64 << i]));
+        assert!(F::omega_pow_inv(i) == F::ROOT_OF_UNITY_INV.pow([1u64 << i]));
+        assert!(F::half_pow(i as u64) == F::TWO_INV.pow([i as u64]));
     }
 }
+
+#[test]
+#[should_panic]
+
+fn precomputed_fft_data_bounds_test() {
+    use crate::regular_functions_utils::FftPrecomp;
+    use halo2curves::bn256::Fr as F;
+    use halo2curves::ff::PrimeField;
+
+    F::omega_pow(F::S + 1);
+}
@@ -1,3 +1,4 @@
+use crate::error::MsmWitnessError;
 use halo2_backend::arithmetic::{
     self, eval_polynomial, kate_division, parallelize, FftGroup,
 };
@@ -18,10 +19,12 @@ use rand::{random, Rng};
 use rand_core::OsRng;
 use std::{
     cmp,
+    collections::{hash_map::Entry, HashMap},
     fmt::{Display, Formatter},
     iter::*,
-    ops::{Add, Mul, Shl, Shr},
-    time::SystemTime,
+    ops::{Add, AddAssign, Mul, MulAssign, Neg, Shl, Shr, Sub, SubAssign},
+    sync::atomic::{AtomicU8, AtomicUsize, Ordering},
+    time::{Instant, SystemTime},
 };
 use subtle::CtOption;
 
@@ -36,10 +39,93 @@ pub trait FftPrecomp {
     fn half_pow(exp: u64) -> Self;
 }
 
+/// Tables of powers used by FftPrecomp implementations generated by impl_fft_precomp,
+/// omega[i] = omega^(2^i), omega_inv[i] = omega^(-2^i), half[i] = (1/2)^i for i from 0 to S.
+pub struct FftTables<F: PrimeField> {
+    pub omega: Vec<F>,
+    pub omega_inv: Vec<F>,
+    pub half: Vec<F>,
+}
+
+impl<F: PrimeField> FftTables<F> {
+    pub fn new() -> Self {
+        let square_powers = |x: F| {
+            let mut acc = x;
+            (0..=F::S)
+                .map(|_| {
+                    let tmp = acc;
+                    acc = acc.square();
+                    tmp
+                })
+                .collect()
+        };
+        let mut acc = F::ONE;
+        let half = (0..=F::S)
+            .map(|_| {
+                let tmp = acc;
+                acc *= F::TWO_INV;
+                tmp
+            })
+            .collect();
+        FftTables {
+            omega: square_powers(F::ROOT_OF_UNITY),
+            omega_inv: square_powers(F::ROOT_OF_UNITY_INV),
+            half,
+        }
+    }
+}
+
+impl<F: PrimeField> Default for FftTables<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Implements FftPrecomp for a prime field, with tables computed from PrimeField::ROOT_OF_UNITY and TWO_INV
+/// on the first call. Fields with hardcoded tables (see precomputed_fft_data.rs) don't need it.
+#[macro_export]
+macro_rules! impl_fft_precomp {
+    ($field:ty) => {
+        const _: () = {
+            fn tables() -> &'static $crate::regular_functions_utils::FftTables<$field> {
+                static TABLES: std::sync::OnceLock<
+                    $crate::regular_functions_utils::FftTables<$field>,
+                > = std::sync::OnceLock::new();
+                TABLES.get_or_init($crate::regular_functions_utils::FftTables::new)
+            }
+
+            impl $crate::regular_functions_utils::FftPrecomp for $field {
+                fn omega_pow(exp2: u32) -> Self {
+                    tables().omega[exp2 as usize]
+                }
+
+                fn omega_pow_inv(exp2: u32) -> Self {
+                    tables().omega_inv[exp2 as usize]
+                }
+
+                fn half_pow(exp: u64) -> Self {
+                    tables().half[exp as usize]
+                }
+            }
+        };
+    };
+}
+
+impl_fft_precomp!(halo2curves::bn256::Fq);
+impl_fft_precomp!(halo2curves::pasta::Fp);
+impl_fft_precomp!(halo2curves::pasta::Fq);
+impl_fft_precomp!(halo2curves::secp256k1::Fp);
+impl_fft_precomp!(halo2curves::secp256k1::Fq);
+impl_fft_precomp!(halo2curves::secp256r1::Fp);
+impl_fft_precomp!(halo2curves::secp256r1::Fq);
+
 #[derive(Clone)]
+/// Polynomial with coefficients in increasing degree order. Constructors and operations keep it trimmed,
+/// i.e. without leading zero coefficients; the zero polynomial has no coefficients at all.
+/// The coefficients are private so that nothing outside can break this, see coeffs.
 pub struct Polynomial<F: PrimeField + FftPrecomp> {
     // this seems to be re-doing some work from halo2::poly...
-    pub poly: Vec<F>,
+    poly: Vec<F>,
 }
 
 pub fn poly<T: IntoIterator>(it: T) -> Polynomial<T::Item>
@@ -51,22 +137,226 @@ where
 
 impl<F: PrimeField + FftPrecomp> Polynomial<F> {
     pub fn new(poly: Vec<F>) -> Self {
-        Polynomial { poly }
+        let mut ret = Polynomial { poly };
+        ret.trim();
+        ret
+    }
+
+    /// coefficients in increasing degree order, the last one is nonzero
+    pub fn coeffs(&self) -> &[F] {
+        &self.poly
+    }
+
+    /// removes leading zero coefficients
+    pub fn trim(&mut self) {
+        while self.poly.last() == Some(&F::ZERO) {
+            self.poly.pop();
+        }
+    }
+
+    /// None for the zero polynomial
+    pub fn degree(&self) -> Option<usize> {
+        self.poly.iter().rposition(|x| *x != F::ZERO)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.degree().is_none()
+    }
+
+    /// Coefficients in increasing degree order, padded with zeros to len.
+    pub fn padded(&self, len: usize) -> Result<Vec<F>, MsmWitnessError> {
+        if let Some(degree) = self.degree().filter(|d| *d >= len) {
+            return Err(MsmWitnessError::DegreeBound {
+                degree,
+                coeffs: len,
+            });
+        }
+        Ok(self
+            .poly
+            .iter()
+            .copied()
+            .chain(repeat(F::ZERO))
+            .take(len)
+            .collect())
     }
 
     pub fn ev(&self, x: F) -> F {
         eval_polynomial(&self.poly, x)
     }
 
+    /// Divides by (x - b), discarding the remainder.
     pub fn kate_div(&self, b: F) -> Self {
+        // kate_division expects at least one coefficient, and the zero polynomial is stored trimmed
+        if self.poly.is_empty() {
+            return Polynomial::new(vec![]);
+        }
         Polynomial::new(kate_division(&self.poly, b))
     }
 
+    /// Divides by (x - b), failing if the remainder (i.e. the value in b) is nonzero.
+    pub fn kate_div_exact(&self, b: F) -> Result<Self, MsmWitnessError> {
+        if self.ev(b) != F::ZERO {
+            return Err(MsmWitnessError::InexactDivision);
+        }
+        Ok(self.kate_div(b))
+    }
+
+    /// Division with remainder, returns (quotient, remainder). Leading zeros of the divisor are ignored,
+    /// panics if the divisor is zero. Large divisions go through power series inversion.
+    pub fn div_rem(&self, divisor: &Self) -> (Self, Self) {
+        let mut d = divisor.poly.len();
+        while d > 0 && divisor.poly[d - 1] == F::ZERO {
+            d -= 1;
+        }
+        assert!(d > 0, "division by zero polynomial");
+        if self.poly.len() < d {
+            return (Polynomial::new(vec![]), self.clone());
+        }
+        if d < 64 || self.poly.len() - d < 64 {
+            return self.div_rem_naive(&divisor.poly[..d]);
+        }
+
+        // reversed quotient is reversed self divided by reversed divisor, modulo x^(deg quotient + 1)
+        let quot_len = self.poly.len() - d + 1;
+        let rev = |p: &[F]| Polynomial::new(p.iter().rev().copied().collect());
+        // the constant term of the reversed divisor is its leading coefficient, which is nonzero
+        let rev_div_inv = rev(&divisor.poly[..d])
+            .inv_series(quot_len)
+            .expect("leading coefficient is nonzero");
+        let rev_quot = (&rev(&self.poly) * &rev_div_inv).truncated(quot_len);
+        let quot = Polynomial::new(
+            rev_quot
+                .poly
+                .iter()
+                .copied()
+                .chain(repeat(F::ZERO))
+                .take(quot_len)
+                .collect::<Vec<_>>()
+                .into_iter()
+                .rev()
+                .collect(),
+        );
+        let rem = (self - &(&quot * divisor)).truncated(d - 1);
+        (quot, rem)
+    }
+
+    fn div_rem_naive(&self, divisor: &[F]) -> (Self, Self) {
+        let d = divisor.len();
+        let lead_inv = divisor[d - 1].invert().unwrap();
+        let mut rem = self.poly.clone();
+        let mut quot = vec![F::ZERO; self.poly.len() - d + 1];
+        for i in (0..quot.len()).rev() {
+            let c = rem[i + d - 1] * lead_inv;
+            quot[i] = c;
+            for j in 0..d {
+                rem[i + j] -= c * divisor[j];
+            }
+        }
+        rem.truncate(d - 1);
+        (Polynomial::new(quot), Polynomial::new(rem))
+    }
+
+    /// Monic greatest common divisor, zero if both polynomials are zero.
+    pub fn gcd(&self, other: &Self) -> Self {
+        let mut a = self.clone();
+        let mut b = other.clone();
+        while !b.is_zero() {
+            let r = a.div_rem(&b).1;
+            a = b;
+            b = r;
+        }
+        match a.poly.last() {
+            None => a,
+            Some(lead) => &a * lead.invert().unwrap(),
+        }
+    }
+
+    /// Returns self^exp modulo m, exp is given as little-endian bytes.
+    pub fn pow_mod(&self, exp: &[u8], m: &Self) -> Self {
+        let mut ret = poly([F::ONE]).div_rem(m).1;
+        for byte in exp.iter().rev() {
+            for i in (0..8).rev() {
+                ret = (&ret * &ret).div_rem(m).1;
+                if (byte >> i) & 1 == 1 {
+                    ret = (&ret * self).div_rem(m).1;
+                }
+            }
+        }
+        ret
+    }
+
+    /// remainder modulo x^len
+    pub fn truncated(&self, len: usize) -> Self {
+        Polynomial::new(self.poly.iter().take(len).copied().collect())
+    }
+
+    /// Inverse as a power series modulo x^len, computed by Newton iteration g <- g(2 - fg).
+    /// Fails if the constant term is zero.
+    pub fn inv_series(&self, len: usize) -> Result<Self, MsmWitnessError> {
+        let c = self.poly.first().copied().unwrap_or(F::ZERO);
+        let c_inv: Option<F> = c.invert().into();
+        let mut g = Polynomial::new(vec![c_inv.ok_or(MsmWitnessError::NotInvertible)?]);
+        let mut k = 1;
+        while k < len {
+            k = cmp::min(2 * k, len);
+            let fg = (&self.truncated(k) * &g).truncated(k);
+            g = (&g * &(&poly([F::from(2)]) - &fg)).truncated(k);
+        }
+        Ok(g.truncated(len))
+    }
+
+    /// Monic polynomial vanishing exactly in the roots (with multiplicities).
+    pub fn from_roots(roots: &[F]) -> Self {
+        SubproductTree::new(roots).product
+    }
+
+    /// Lagrange interpolation through the given (x, y) pairs, using a subproduct tree.
+    /// Fails with DuplicateX if the x coordinates are not distinct.
+    pub fn interpolate(pts: &[(F, F)]) -> Result<Self, MsmWitnessError> {
+        let xs: Vec<F> = pts.iter().map(|(x, _)| *x).collect();
+        let tree = SubproductTree::new(&xs);
+        // values of the derivative of the vanishing polynomial are the denominators of the Lagrange basis
+        let mut ws = Vec::with_capacity(xs.len());
+        tree.ev(&tree.product.derivative(), &xs, &mut ws);
+        if ws.iter().any(|w| bool::from(w.is_zero())) {
+            return Err(MsmWitnessError::DuplicateX);
+        }
+        ws.iter_mut().batch_invert();
+        let ws: Vec<F> = ws.into_iter().zip(pts).map(|(w, (_, y))| w * y).collect();
+        Ok(tree.linear_combination(&xs, &ws))
+    }
+
+    /// Evaluates the polynomial in many points at once, using a subproduct tree.
+    pub fn ev_many(&self, xs: &[F]) -> Vec<F> {
+        if xs.len() <= SUBPRODUCT_LEAF {
+            return xs.iter().map(|x| self.ev(*x)).collect();
+        }
+        let tree = SubproductTree::new(xs);
+        let mut ret = Vec::with_capacity(xs.len());
+        tree.ev(self, xs, &mut ret);
+        ret
+    }
+
     pub fn scale(&self, sc: F) -> Self {
         Polynomial::new((&self.poly).into_iter().map(|x| *x * sc).collect())
     }
 
+    /// formal derivative
+    pub fn derivative(&self) -> Self {
+        Polynomial::new(
+            (&self.poly)
+                .into_iter()
+                .enumerate()
+                .skip(1)
+                .map(|(i, x)| *x * F::from(i as u64))
+                .collect(),
+        )
+    }
+
     pub fn mul_naive(a: &Self, b: &Self) -> Self {
+        if a.poly.is_empty() || b.poly.is_empty() {
+            return Polynomial::new(vec![]);
+        }
         let mut ret: Vec<F> = repeat(F::ZERO)
             .take(a.poly.len() + b.poly.len() - 1)
             .collect();
@@ -78,12 +368,12 @@ impl<F: PrimeField + FftPrecomp> Polynomial<F> {
         Polynomial::new(ret)
     }
 
-    /// according to my tests, this functions is absolutely useless;
-    /// either naive or fft multiplication is always better
+    /// Karatsuba multiplication, falling back to the naive one below KARATSUBA_LEAF coefficients;
+    /// works best for balanced operands, see mul_with for the unbalanced case.
     pub fn mul_karatsuba(a: &Self, b: &Self) -> Self {
         let d = cmp::max(a.poly.len(), b.poly.len()) / 2;
 
-        if a.poly.len() <= 1 || b.poly.len() <= 1 {
+        if cmp::min(a.poly.len(), b.poly.len()) < KARATSUBA_LEAF {
             return Self::mul_naive(a, b);
         }
 
@@ -116,7 +406,7 @@ impl<F: PrimeField + FftPrecomp> Polynomial<F> {
 
         let m0 = Self::mul_karatsuba(&a_l, &b_l);
         let m2 = Self::mul_karatsuba(&a_r, &b_r);
-        let m1 = &Self::mul_karatsuba(&a_s, &b_s) + &(&m0 + &m2).scale(-F::ONE);
+        let m1 = &Self::mul_karatsuba(&a_s, &b_s) - &(&m0 + &m2);
 
         // answer is m0 + m1 x^d + m2 x^{2d}
 
@@ -124,6 +414,9 @@ impl<F: PrimeField + FftPrecomp> Polynomial<F> {
     }
 
     pub fn mul_fft(&self, other: &Self) -> Self {
+        if self.poly.is_empty() || other.poly.is_empty() {
+            return Polynomial::new(vec![]);
+        }
         let length = self.poly.len() + other.poly.len() - 1;
         let loglength = log2_floor(length) + 1;
 
@@ -162,6 +455,324 @@ impl<F: PrimeField + FftPrecomp> Polynomial<F> {
 
         Polynomial::new(prod.into_iter().take(length).collect())
     }
+
+    /// Multiplies using the given strategy; Auto picks one by the length of the shorter operand, see MulThresholds.
+    /// If one operand is at least twice longer, it is split into chunks of the length of the other one.
+    pub fn mul_with(&self, other: &Self, strategy: MulStrategy) -> Self {
+        let (long, short) = if self.poly.len() >= other.poly.len() {
+            (self, other)
+        } else {
+            (other, self)
+        };
+        if short.poly.is_empty() {
+            return Polynomial::new(vec![]);
+        }
+
+        let unbalanced = long.poly.len() >= 2 * short.poly.len();
+        let chunk_len = if unbalanced {
+            short.poly.len()
+        } else {
+            long.poly.len()
+        };
+
+        let strategy = strategy.resolve(short.poly.len());
+        let mul: fn(&Self, &Self) -> Self = match strategy {
+            MulStrategy::Naive => return Self::mul_naive(long, short),
+            // fields of small 2-adicity (e.g. secp256k1) don't have large enough roots of unity
+            MulStrategy::Fft if log2_floor(chunk_len + short.poly.len() - 1) + 1 <= F::S => {
+                Self::mul_fft
+            }
+            _ => Self::mul_karatsuba,
+        };
+
+        if !unbalanced {
+            return mul(long, short);
+        }
+        let mut ret = vec![F::ZERO; long.poly.len() + short.poly.len() - 1];
+        for (i, chunk) in long.poly.chunks(short.poly.len()).enumerate() {
+            let prod = mul(&Polynomial::new(chunk.to_vec()), short);
+            for (x, y) in ret[i * short.poly.len()..].iter_mut().zip(prod.poly) {
+                *x += y;
+            }
+        }
+        Polynomial::new(ret)
+    }
+}
+
+/// Below this length Karatsuba multiplication recurses into the naive one.
+const KARATSUBA_LEAF: usize = 16;
+
+/// How polynomials are multiplied, see Polynomial::mul_with and set_mul_strategy.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MulStrategy {
+    Naive,
+    Karatsuba,
+    Fft,
+    /// chooses by the length of the shorter operand, see MulThresholds
+    #[default]
+    Auto,
+}
+
+/// Crossover points used by MulStrategy::Auto, in coefficients of the shorter operand: FFT is used from fft on,
+/// Karatsuba from karatsuba on (if below fft), and naive multiplication below both.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MulThresholds {
+    pub karatsuba: usize,
+    pub fft: usize,
+}
+
+/// default crossover for both thresholds, i.e. naive below it and FFT from it on
+pub const DEFAULT_MUL_THRESHOLD: usize = 32;
+
+impl Default for MulThresholds {
+    /// naive below DEFAULT_MUL_THRESHOLD coefficients, FFT otherwise
+    fn default() -> Self {
+        MulThresholds {
+            karatsuba: DEFAULT_MUL_THRESHOLD,
+            fft: DEFAULT_MUL_THRESHOLD,
+        }
+    }
+}
+
+impl MulStrategy {
+    /// replaces Auto by the strategy picked for operands the shorter of which has len coefficients
+    pub fn resolve(self, len: usize) -> Self {
+        match self {
+            MulStrategy::Auto => mul_thresholds().pick(len),
+            s => s,
+        }
+    }
+}
+
+impl MulThresholds {
+    pub fn pick(&self, len: usize) -> MulStrategy {
+        if len >= self.fft {
+            MulStrategy::Fft
+        } else if len >= self.karatsuba {
+            MulStrategy::Karatsuba
+        } else {
+            MulStrategy::Naive
+        }
+    }
+}
+
+static MUL_STRATEGY: AtomicU8 = AtomicU8::new(MulStrategy::Auto as u8);
+static KARATSUBA_THRESHOLD: AtomicUsize = AtomicUsize::new(DEFAULT_MUL_THRESHOLD);
+static FFT_THRESHOLD: AtomicUsize = AtomicUsize::new(DEFAULT_MUL_THRESHOLD);
+
+/// Sets the strategy used by the * operator on polynomials, for all fields and threads.
+pub fn set_mul_strategy(strategy: MulStrategy) {
+    MUL_STRATEGY.store(strategy as u8, Ordering::Relaxed);
+}
+
+pub fn mul_strategy() -> MulStrategy {
+    match MUL_STRATEGY.load(Ordering::Relaxed) {
+        0 => MulStrategy::Naive,
+        1 => MulStrategy::Karatsuba,
+        2 => MulStrategy::Fft,
+        _ => MulStrategy::Auto,
+    }
+}
+
+/// Sets the thresholds used by MulStrategy::Auto, for all fields and threads.
+pub fn set_mul_thresholds(thresholds: MulThresholds) {
+    KARATSUBA_THRESHOLD.store(thresholds.karatsuba, Ordering::Relaxed);
+    FFT_THRESHOLD.store(thresholds.fft, Ordering::Relaxed);
+}
+
+pub fn mul_thresholds() -> MulThresholds {
+    MulThresholds {
+        karatsuba: KARATSUBA_THRESHOLD.load(Ordering::Relaxed),
+        fft: FFT_THRESHOLD.load(Ordering::Relaxed),
+    }
+}
+
+/// Measures on this machine the shortest balanced operands (among powers of two up to 1024) for which Karatsuba
+/// beats naive multiplication and FFT beats both. Nothing is stored, see calibrate_and_store_mul_thresholds.
+pub fn calibrate_mul_thresholds<F: PrimeField + FftPrecomp>() -> MulThresholds {
+    let time = |strategy: MulStrategy, len: usize| {
+        let p = Polynomial::new((0..len).map(|_| F::random(OsRng)).collect());
+        let q = Polynomial::new((0..len).map(|_| F::random(OsRng)).collect());
+        let start = Instant::now();
+        for _ in 0..cmp::max(1, 1024 / len) {
+            let _ = p.mul_with(&q, strategy);
+        }
+        start.elapsed()
+    };
+    let lens: Vec<usize> = (1..=10).map(|i| 1 << i).collect();
+
+    let karatsuba = lens
+        .iter()
+        .copied()
+        .find(|len| time(MulStrategy::Karatsuba, *len) < time(MulStrategy::Naive, *len))
+        .unwrap_or(usize::MAX);
+    let fft = lens
+        .iter()
+        .copied()
+        .find(|len| {
+            let fft = time(MulStrategy::Fft, *len);
+            fft < time(MulStrategy::Naive, *len) && fft < time(MulStrategy::Karatsuba, *len)
+        })
+        .unwrap_or(usize::MAX);
+
+    MulThresholds { karatsuba, fft }
+}
+
+/// Runs calibrate_mul_thresholds and stores the result with set_mul_thresholds. The thresholds are shared
+/// by all fields, so calibrate with the one used the most.
+pub fn calibrate_and_store_mul_thresholds<F: PrimeField + FftPrecomp>() -> MulThresholds {
+    let thresholds = calibrate_mul_thresholds::<F>();
+    set_mul_thresholds(thresholds);
+    thresholds
+}
+
+/// Values of a polynomial on the 2^log_size-th roots of unity. Sums and products are pointwise, so a polynomial
+/// expression takes one forward FFT per operand and a single inverse FFT at the end, as long as the degree of
+/// the result stays below 2^log_size.
+#[derive(Clone, Debug)]
+pub struct PolynomialEval<F: PrimeField + FftPrecomp> {
+    log_size: u32,
+    values: Vec<F>,
+}
+
+impl<F: PrimeField + FftPrecomp> PolynomialEval<F> {
+    /// smallest log_size fitting a polynomial with len coefficients
+    pub fn log_size_for(len: usize) -> u32 {
+        if len <= 1 {
+            0
+        } else {
+            log2_floor(len - 1) + 1
+        }
+    }
+
+    /// Panics if p has more than 2^log_size coefficients, or log_size exceeds the two-adicity of the field.
+    pub fn new(p: &Polynomial<F>, log_size: u32) -> Self {
+        assert!(log_size <= F::S, "log_size exceeds the two-adicity S");
+        let size = 1 << log_size;
+        assert!(
+            p.poly.len() <= size,
+            "polynomial does not fit into the domain"
+        );
+        let mut values = p.poly.clone();
+        values.resize(size, F::ZERO);
+        best_fft(&mut values, F::omega_pow(F::S - log_size), log_size);
+        PolynomialEval { log_size, values }
+    }
+
+    pub fn log_size(&self) -> u32 {
+        self.log_size
+    }
+
+    /// Interpolates the values with an inverse FFT.
+    pub fn into_poly(mut self) -> Polynomial<F> {
+        best_fft(
+            &mut self.values,
+            F::omega_pow_inv(F::S - self.log_size),
+            self.log_size,
+        );
+        let scaling = F::half_pow(self.log_size as u64);
+        for x in self.values.iter_mut() {
+            *x *= scaling;
+        }
+        Polynomial::new(self.values)
+    }
+
+    fn pointwise(&self, other: &Self, op: impl Fn(F, F) -> F) -> Self {
+        assert_eq!(self.log_size, other.log_size, "different domains");
+        PolynomialEval {
+            log_size: self.log_size,
+            values: self
+                .values
+                .iter()
+                .zip(other.values.iter())
+                .map(|(x, y)| op(*x, *y))
+                .collect(),
+        }
+    }
+}
+
+impl<F: PrimeField + FftPrecomp> Add for &PolynomialEval<F> {
+    type Output = PolynomialEval<F>;
+
+    fn add(self, other: Self) -> Self::Output {
+        self.pointwise(other, |x, y| x + y)
+    }
+}
+
+impl<F: PrimeField + FftPrecomp> Sub for &PolynomialEval<F> {
+    type Output = PolynomialEval<F>;
+
+    fn sub(self, other: Self) -> Self::Output {
+        self.pointwise(other, |x, y| x - y)
+    }
+}
+
+impl<F: PrimeField + FftPrecomp> Mul for &PolynomialEval<F> {
+    type Output = PolynomialEval<F>;
+
+    fn mul(self, other: Self) -> Self::Output {
+        self.pointwise(other, |x, y| x * y)
+    }
+}
+
+/// Below this amount of points evaluation is done by Horner's method.
+const SUBPRODUCT_LEAF: usize = 64;
+
+/// Products of (x - x_i) over halves, quarters, etc. of the points, down to SUBPRODUCT_LEAF points.
+struct SubproductTree<F: PrimeField + FftPrecomp> {
+    product: Polynomial<F>,
+    children: Option<Box<(SubproductTree<F>, SubproductTree<F>)>>,
+}
+
+impl<F: PrimeField + FftPrecomp> SubproductTree<F> {
+    fn new(xs: &[F]) -> Self {
+        if xs.len() <= SUBPRODUCT_LEAF {
+            let product = xs
+                .iter()
+                .fold(poly([F::ONE]), |acc, x| &acc * &poly([-*x, F::ONE]));
+            return SubproductTree {
+                product,
+                children: None,
+            };
+        }
+        let (l, r) = xs.split_at(xs.len() / 2);
+        let (l, r) = (Self::new(l), Self::new(r));
+        SubproductTree {
+            product: &l.product * &r.product,
+            children: Some(Box::new((l, r))),
+        }
+    }
+
+    /// returns sum ws_i * product / (x - xs_i), where xs are the points the tree was built from
+    fn linear_combination(&self, xs: &[F], ws: &[F]) -> Polynomial<F> {
+        match &self.children {
+            None => xs
+                .iter()
+                .zip(ws)
+                .fold(Polynomial::new(vec![]), |acc, (x, w)| {
+                    &acc + &(&self.product.kate_div(*x) * *w)
+                }),
+            Some(children) => {
+                let mid = xs.len() / 2;
+                let l = children.0.linear_combination(&xs[..mid], &ws[..mid]);
+                let r = children.1.linear_combination(&xs[mid..], &ws[mid..]);
+                &(&l * &children.1.product) + &(&r * &children.0.product)
+            }
+        }
+    }
+
+    /// pushes values of p in xs, which are the points the tree was built from
+    fn ev(&self, p: &Polynomial<F>, xs: &[F], ret: &mut Vec<F>) {
+        let p = p.div_rem(&self.product).1;
+        match &self.children {
+            None => ret.extend(xs.iter().map(|x| p.ev(*x))),
+            Some(children) => {
+                let (l, r) = xs.split_at(xs.len() / 2);
+                children.0.ev(&p, l, ret);
+                children.1.ev(&p, r, ret);
+            }
+        }
+    }
 }
 
 impl<F: PrimeField + FftPrecomp> Display for Polynomial<F> {
@@ -189,6 +800,16 @@ impl<F: PrimeField + FftPrecomp> Display for Polynomial<F> {
     }
 }
 
+/// Coefficient-wise equality, ignoring leading zeros.
+impl<F: PrimeField + FftPrecomp> PartialEq for Polynomial<F> {
+    fn eq(&self, other: &Self) -> bool {
+        let len = self.degree().map_or(0, |d| d + 1);
+        self.degree() == other.degree() && self.poly[..len] == other.poly[..len]
+    }
+}
+
+impl<F: PrimeField + FftPrecomp> Eq for Polynomial<F> {}
+
 impl<F: PrimeField + FftPrecomp> Shr<usize> for &Polynomial<F> {
     type Output = Polynomial<F>;
 
@@ -237,10 +858,118 @@ impl<F: PrimeField + FftPrecomp> Mul for &Polynomial<F> {
     type Output = Polynomial<F>;
 
     fn mul(self, other: Self) -> Self::Output {
-        if (*self).poly.len() < 32 || other.poly.len() < 32 {
-            return Polynomial::mul_naive(self, other);
+        self.mul_with(other, mul_strategy())
+    }
+}
+
+impl<F: PrimeField + FftPrecomp> Sub for &Polynomial<F> {
+    type Output = Polynomial<F>;
+
+    fn sub(self, other: Self) -> Self::Output {
+        let mut ret = self.clone();
+        ret -= other;
+        ret
+    }
+}
+
+impl<F: PrimeField + FftPrecomp> Neg for &Polynomial<F> {
+    type Output = Polynomial<F>;
+
+    fn neg(self) -> Self::Output {
+        -self.clone()
+    }
+}
+
+impl<F: PrimeField + FftPrecomp> Neg for Polynomial<F> {
+    type Output = Polynomial<F>;
+
+    fn neg(mut self) -> Self::Output {
+        for x in self.poly.iter_mut() {
+            *x = -*x;
+        }
+        self
+    }
+}
+
+impl<F: PrimeField + FftPrecomp> AddAssign<&Polynomial<F>> for Polynomial<F> {
+    fn add_assign(&mut self, other: &Polynomial<F>) {
+        if self.poly.len() < other.poly.len() {
+            self.poly.resize(other.poly.len(), F::ZERO);
+        }
+        for (x, y) in self.poly.iter_mut().zip(other.poly.iter()) {
+            *x += y;
+        }
+        self.trim();
+    }
+}
+
+impl<F: PrimeField + FftPrecomp> SubAssign<&Polynomial<F>> for Polynomial<F> {
+    fn sub_assign(&mut self, other: &Polynomial<F>) {
+        if self.poly.len() < other.poly.len() {
+            self.poly.resize(other.poly.len(), F::ZERO);
+        }
+        for (x, y) in self.poly.iter_mut().zip(other.poly.iter()) {
+            *x -= y;
+        }
+        self.trim();
+    }
+}
+
+impl<F: PrimeField + FftPrecomp> MulAssign<&Polynomial<F>> for Polynomial<F> {
+    fn mul_assign(&mut self, other: &Polynomial<F>) {
+        *self = &*self * other;
+    }
+}
+
+impl<F: PrimeField + FftPrecomp> MulAssign<F> for Polynomial<F> {
+    fn mul_assign(&mut self, sc: F) {
+        for x in self.poly.iter_mut() {
+            *x *= sc;
         }
-        self.mul_fft(other)
+        self.trim();
+    }
+}
+
+impl<F: PrimeField + FftPrecomp> Mul<F> for &Polynomial<F> {
+    type Output = Polynomial<F>;
+
+    fn mul(self, sc: F) -> Self::Output {
+        self.scale(sc)
+    }
+}
+
+impl<F: PrimeField + FftPrecomp> Mul<F> for Polynomial<F> {
+    type Output = Polynomial<F>;
+
+    fn mul(mut self, sc: F) -> Self::Output {
+        self *= sc;
+        self
+    }
+}
+
+impl<F: PrimeField + FftPrecomp> Add for Polynomial<F> {
+    type Output = Polynomial<F>;
+
+    fn add(mut self, other: Self) -> Self::Output {
+        self += &other;
+        self
+    }
+}
+
+impl<F: PrimeField + FftPrecomp> Sub for Polynomial<F> {
+    type Output = Polynomial<F>;
+
+    fn sub(mut self, other: Self) -> Self::Output {
+        self -= &other;
+        self
+    }
+}
+
+impl<F: PrimeField + FftPrecomp> Mul for Polynomial<F> {
+    type Output = Polynomial<F>;
+
+    fn mul(self, other: Self) -> Self::Output {
+        &self * &other
     }
 }
 
@@ -259,16 +988,43 @@ where
     C::Base: FftPrecomp,
 {
     pub fn ev(&self, pt: C) -> C::Base {
-        let (x, y, z) = pt.jacobian_coordinates();
-        let zinv = z.invert().unwrap();
-        let zinvsq = zinv * zinv;
-        self.ev_unchecked(x * zinvsq, y * zinvsq * zinv)
+        let (x, y) = affine_coords(&pt).expect("evaluating at the identity");
+        self.ev_unchecked(x, y)
     }
 
     pub fn ev_unchecked(&self, x: C::Base, y: C::Base) -> C::Base {
         self.a.ev(x) + self.b.ev(x) * y
     }
 
+    /// Evaluates the function in many points at once, see Polynomial::ev_many.
+    /// Like ev_dlog, returns DegenerateChallenge if one of the points is the identity.
+    pub fn ev_many(&self, pts: &[C]) -> Result<Vec<C::Base>, MsmWitnessError> {
+        let coords: Vec<(C::Base, C::Base, C::Base)> =
+            pts.iter().map(|pt| pt.jacobian_coordinates()).collect();
+        let mut zinvs: Vec<C::Base> = coords.iter().map(|(_, _, z)| *z).collect();
+        if zinvs.iter().any(|z| bool::from(z.is_zero())) {
+            return Err(MsmWitnessError::DegenerateChallenge);
+        }
+        zinvs.iter_mut().batch_invert();
+
+        let (xs, ys): (Vec<C::Base>, Vec<C::Base>) = coords
+            .iter()
+            .zip(zinvs)
+            .map(|((x, y, _), zinv)| {
+                let zinvsq = zinv * zinv;
+                (*x * zinvsq, *y * zinvsq * zinv)
+            })
+            .unzip();
+        Ok(self
+            .a
+            .ev_many(&xs)
+            .into_iter()
+            .zip(self.b.ev_many(&xs))
+            .zip(ys)
+            .map(|((a, b), y)| a + b * y)
+            .collect())
+    }
+
     pub fn from_const(x: C::Base) -> Self {
         Self::new(Polynomial::new(vec![x]), Polynomial::new(vec![]))
     }
@@ -282,14 +1038,200 @@ where
         RegularFunction { a, b }
     }
 
+    pub fn is_zero(&self) -> bool {
+        self.a.is_zero() && self.b.is_zero()
+    }
+
+    /// Returns a(x) - y*b(x), i.e. the function composed with P -> -P.
+    pub fn conjugate(&self) -> Self {
+        Self::new(self.a.clone(), -&self.b)
+    }
+
+    /// order of the pole at infinity, max(2 deg a, 2 deg b + 3), or 0 for constants
+    pub fn pole_order(&self) -> usize {
+        cmp::max(
+            self.a.degree().map_or(0, |d| 2 * d),
+            self.b.degree().map_or(0, |d| 2 * d + 3),
+        )
+    }
+
+    /// Product of the functions. If FFT multiplication is used (see MulStrategy), all the polynomials and
+    /// x^3 + ax + b are transformed once into a domain fitting the result, multiplied pointwise (see PolynomialEval),
+    /// and a and b of the product are recovered with one inverse FFT each.
+    pub fn product(fs: &[&Self]) -> Self {
+        // pole orders add up, which bounds the degrees of the product and of all partial products
+        let (deg_a, _) = Self::degree_bounds(fs.iter().map(|f| f.pole_order()).sum());
+        let log_size = PolynomialEval::<C::Base>::log_size_for(cmp::max(deg_a + 1, 4));
+        let short = fs
+            .iter()
+            .map(|f| cmp::max(f.a.poly.len(), f.b.poly.len()))
+            .min()
+            .unwrap_or(0);
+        if fs.len() < 2
+            || log_size > C::Base::S
+            || mul_strategy().resolve(short) != MulStrategy::Fft
+        {
+            return fs
+                .iter()
+                .fold(Self::from_const(C::Base::ONE), |acc, f| acc.mul_coeffs(f));
+        }
+
+        let ev = |p: &Polynomial<C::Base>| PolynomialEval::new(p, log_size);
+        let subst_y2 = ev(&Polynomial::new(vec![
+            C::b(),
+            C::a(),
+            C::Base::ZERO,
+            C::Base::ONE,
+        ])); // x^3 + ax + b
+        let (mut a, mut b) = (ev(&fs[0].a), ev(&fs[0].b));
+        for f in &fs[1..] {
+            let (fa, fb) = (ev(&f.a), ev(&f.b));
+            let new_a = &(&a * &fa) + &(&(&b * &fb) * &subst_y2);
+            b = &(&a * &fb) + &(&b * &fa);
+            a = new_a;
+        }
+        RegularFunction::new(a.into_poly(), b.into_poly())
+    }
+
+    /// multiplication on coefficients, substituting y^2 = x^3 + ax + b
+    fn mul_coeffs(&self, other: &Self) -> Self {
+        let subst_y2 = Polynomial::new(vec![C::b(), C::a(), C::Base::ZERO, C::Base::ONE]); // x^3 + ax + b
+        RegularFunction::new(
+            &(&self.a * &other.a) + &(&(&self.b * &other.b) * &subst_y2),
+            &(&self.a * &other.b) + &(&self.b * &other.a),
+        )
+    }
+
+    /// Scales the function so that the leading coefficient of a is one (or of b, if a is zero).
+    /// Functions with the same divisor have the same normalization.
+    pub fn normalize(&self) -> Self {
+        match self.a.poly.last().or(self.b.poly.last()) {
+            None => self.clone(),
+            Some(lead) => self.scale(lead.invert().unwrap()),
+        }
+    }
+
+    /// Checks that the functions are equal up to a constant factor, i.e. have the same zeros and poles.
+    /// Same as ==, which compares normalized functions.
+    pub fn same_divisor(&self, other: &Self) -> bool {
+        self == other
+    }
+
+    /// Maximal degrees of a and b for a function with at most n zeros counted with multiplicities, e.g. the witness
+    /// computed by compute_divisor_witness for n points. x and y have poles of order 2 and 3 at infinity, so
+    /// deg a <= n/2 and deg b <= (n-3)/2; None means that b has to be zero.
+    pub fn degree_bounds(n: usize) -> (usize, Option<usize>) {
+        (n / 2, if n >= 3 { Some((n - 3) / 2) } else { None })
+    }
+
+    /// Amounts of coefficients of a and b needed to fit any function with at most n zeros, see degree_bounds.
+    pub fn num_coeffs(n: usize) -> (usize, usize) {
+        let (deg_a, deg_b) = Self::degree_bounds(n);
+        (deg_a + 1, deg_b.map_or(0, |x| x + 1))
+    }
+
+    pub fn validate_degree_bounds(&self, n: usize) -> Result<(), MsmWitnessError> {
+        let (len_a, len_b) = Self::num_coeffs(n);
+        for (p, len) in [(&self.a, len_a), (&self.b, len_b)] {
+            if let Some(degree) = p.degree() {
+                if degree >= len {
+                    return Err(MsmWitnessError::DegreeBound {
+                        degree,
+                        coeffs: len,
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Coefficients of a and b in increasing degree order, padded to the lengths given by num_coeffs.
+    pub fn padded_coeffs(&self, n: usize) -> Result<(Vec<C::Base>, Vec<C::Base>), MsmWitnessError> {
+        let (len_a, len_b) = Self::num_coeffs(n);
+        Ok((self.a.padded(len_a)?, self.b.padded(len_b)?))
+    }
+
     pub fn scale(&self, sc: C::Base) -> Self {
         RegularFunction {
             a: self.a.scale(sc),
             b: self.b.scale(sc),
         }
     }
+
+    /// In-place version of scale. Scalar operators are only implemented for Polynomial,
+    /// as Mul<C::Base> would overlap with Mul<RegularFunction<C>> for the compiler.
+    pub fn scale_assign(&mut self, sc: C::Base) {
+        self.a *= sc;
+        self.b *= sc;
+    }
+
+    /// Returns df / omega, where omega = dx / 2y is the invariant differential of the curve.
+    /// dy is eliminated using 2y dy = (3x^2 + a) dx, so for f = a(x) + y*b(x) this is
+    /// 2(x^3 + ax + b) b'(x) + (3x^2 + a) b(x) + y * 2a'(x).
+    pub fn derivative(&self) -> Self {
+        let subst_y2 = Polynomial::new(vec![C::b(), C::a(), C::Base::ZERO, C::Base::ONE]); // x^3 + ax + b
+        let subst_dy = Polynomial::new(vec![C::a(), C::Base::ZERO, C::Base::from(3)]); // 3x^2 + a
+        let two = C::Base::from(2);
+        RegularFunction::new(
+            &(&subst_y2 * &self.b.derivative()).scale(two) + &(&subst_dy * &self.b),
+            self.a.derivative().scale(two),
+        )
+    }
+
+    /// Order of vanishing of f in pt, found by differentiating (see derivative) until the value is nonzero.
+    /// The invariant differential has neither zeros nor poles, so each derivative lowers the order by exactly one,
+    /// including in points of order 2. Returns None for the zero function or the identity.
+    pub fn vanishing_order(&self, pt: C) -> Option<usize> {
+        let (x, y) = affine_coords(&pt)?;
+        if self.is_zero() {
+            return None;
+        }
+        let mut f = self.clone();
+        let mut order = 0;
+        while f.ev_unchecked(x, y).is_zero_vartime() {
+            f = f.derivative();
+            order += 1;
+        }
+        Some(order)
+    }
+
+    /// Evaluates the logarithmic derivative of f in pt, when pt moves along the curve together with
+    /// the line of given slope passing through it, i.e. d/dmu log f(A) where A lies on y = slope * x + mu.
+    /// Summed over the three intersection points of a line with the curve, it gives minus the sum of
+    /// 1 / (y - slope * x - mu) over the zeros of f, which is the identity of the argument.
+    /// Fails with DegenerateChallenge at the identity, if f vanishes in pt or the line is tangent to the curve in pt.
+    pub fn ev_dlog(&self, pt: C, slope: C::Base) -> Result<C::Base, MsmWitnessError> {
+        Ok(self.ev_dlog_many(&[pt], slope)?[0])
+    }
+
+    /// ev_dlog in several points on lines of the same slope, computing the derivative of f only once.
+    pub fn ev_dlog_many(&self, pts: &[C], slope: C::Base) -> Result<Vec<C::Base>, MsmWitnessError> {
+        let df = self.derivative();
+        pts.iter()
+            .map(|pt| {
+                let (x, y) = affine_coords(pt).ok_or(MsmWitnessError::DegenerateChallenge)?;
+                // dmu / omega, from dmu = dy - slope * dx and 2y dy = (3x^2 + a) dx
+                let dmu = C::Base::from(3) * x * x + C::a() - (slope + slope) * y;
+                let inv: Option<C::Base> = (self.ev_unchecked(x, y) * dmu).invert().into();
+                Ok(df.ev_unchecked(x, y) * inv.ok_or(MsmWitnessError::DegenerateChallenge)?)
+            })
+            .collect()
+    }
 }
 
+/// Equality up to a constant factor, i.e. of the normalized functions. Compare a and b for coefficient-wise equality.
+impl<C: CurveExt> PartialEq for RegularFunction<C>
+where
+    C::Base: FftPrecomp,
+{
+    fn eq(&self, other: &Self) -> bool {
+        let (f, g) = (self.normalize(), other.normalize());
+        f.a == g.a && f.b == g.b
+    }
+}
+
+impl<C: CurveExt> Eq for RegularFunction<C> where C::Base: FftPrecomp {}
+
 impl<C: CurveExt> Add for &RegularFunction<C>
 where
     C::Base: FftPrecomp,
@@ -308,11 +1250,104 @@ where
     type Output = RegularFunction<C>;
 
     fn mul(self, other: Self) -> Self::Output {
-        let subst_y2 = Polynomial::new(vec![C::b(), C::a(), C::Base::ZERO, C::Base::ONE]); // x^3 + ax + b
-        RegularFunction::new(
-            &(&self.a * &other.a) + &(&(&self.b * &other.b) * &subst_y2),
-            &(&self.a * &other.b) + &(&self.b * &other.a),
-        )
+        RegularFunction::product(&[self, other])
+    }
+}
+
+impl<C: CurveExt> Sub for &RegularFunction<C>
+where
+    C::Base: FftPrecomp,
+{
+    type Output = RegularFunction<C>;
+
+    fn sub(self, other: Self) -> Self::Output {
+        RegularFunction::new(&self.a - &other.a, &self.b - &other.b)
+    }
+}
+
+impl<C: CurveExt> Neg for &RegularFunction<C>
+where
+    C::Base: FftPrecomp,
+{
+    type Output = RegularFunction<C>;
+
+    fn neg(self) -> Self::Output {
+        RegularFunction::new(-&self.a, -&self.b)
+    }
+}
+
+impl<C: CurveExt> Neg for RegularFunction<C>
+where
+    C::Base: FftPrecomp,
+{
+    type Output = RegularFunction<C>;
+
+    fn neg(self) -> Self::Output {
+        RegularFunction::new(-self.a, -self.b)
+    }
+}
+
+impl<C: CurveExt> AddAssign<&RegularFunction<C>> for RegularFunction<C>
+where
+    C::Base: FftPrecomp,
+{
+    fn add_assign(&mut self, other: &RegularFunction<C>) {
+        self.a += &other.a;
+        self.b += &other.b;
+    }
+}
+
+impl<C: CurveExt> SubAssign<&RegularFunction<C>> for RegularFunction<C>
+where
+    C::Base: FftPrecomp,
+{
+    fn sub_assign(&mut self, other: &RegularFunction<C>) {
+        self.a -= &other.a;
+        self.b -= &other.b;
+    }
+}
+
+impl<C: CurveExt> MulAssign<&RegularFunction<C>> for RegularFunction<C>
+where
+    C::Base: FftPrecomp,
+{
+    fn mul_assign(&mut self, other: &RegularFunction<C>) {
+        *self = &*self * other;
+    }
+}
+
+impl<C: CurveExt> Add for RegularFunction<C>
+where
+    C::Base: FftPrecomp,
+{
+    type Output = RegularFunction<C>;
+
+    fn add(mut self, other: Self) -> Self::Output {
+        self += &other;
+        self
+    }
+}
+
+impl<C: CurveExt> Sub for RegularFunction<C>
+where
+    C::Base: FftPrecomp,
+{
+    type Output = RegularFunction<C>;
+
+    fn sub(mut self, other: Self) -> Self::Output {
+        self -= &other;
+        self
+    }
+}
+
+impl<C: CurveExt> Mul for RegularFunction<C>
+where
+    C::Base: FftPrecomp,
+{
+    type Output = RegularFunction<C>;
+
+    fn mul(self, other: Self) -> Self::Output {
+        &self * &other
     }
 }
 
@@ -325,7 +1360,7 @@ fn felt_from_u64<Fz: PrimeField>(d: u64) -> Fz {
 // importantly, always using FFT is inefficient, for some ranges Karatsuba is better
 // now, the interesting part starts
 
-/// this function returns a line passing through a pair of points
+/// this function returns a line passing through a pair of points, or the tangent line if they coincide
 pub fn linefunc<C: CurveExt>(a: &C, b: &C) -> RegularFunction<C>
 where
     C::Base: FftPrecomp,
@@ -341,24 +1376,45 @@ where
         return RegularFunction::from_line(lx, ly, lz);
     }
 
-    let c = -(*a + b);
+    // the cross product also vanishes if one of the points is the identity, which can have all coordinates zero;
+    // the line through a point and the identity is the vertical one
+    match (bool::from(a.is_identity()), bool::from(b.is_identity())) {
+        (true, true) => RegularFunction::from_const(C::Base::ONE),
+        (true, false) => linefunc(b, &-*b),
+        (false, true) => linefunc(a, &-*a),
+        (false, false) => tangentfunc(a),
+    }
+}
 
-    let (cx, cy, cz) = projective_coords(&c);
+/// this function returns the tangent line to the curve in a point, vanishing there with multiplicity 2 (or 3
+/// in an inflection point); for a point of order 2 it is the vertical line, and for the identity a constant
+pub fn tangentfunc<C: CurveExt>(pt: &C) -> RegularFunction<C>
+where
+    C::Base: FftPrecomp,
+{
+    if pt.is_identity().into() {
+        return RegularFunction::from_const(C::Base::ONE);
+    }
+    let (x, y, z) = projective_coords(pt);
+    let three = C::Base::from(3);
 
-    return RegularFunction::from_line(ay * cz - az * cy, az * cx - ax * cz, ax * cy - ay * cx);
+    // gradient of Y^2 Z - X^3 - a X Z^2 - b Z^3
+    let lx = -(three * x * x + C::a() * z * z);
+    let ly = (y + y) * z;
+    let lz = y * y - (C::a() + C::a()) * x * z - three * C::b() * z * z;
+
+    RegularFunction::from_line(lx, ly, lz)
 }
 
 #[derive(Clone)]
-/// this struct holds the following data:
-/// 1) vector of "input" points
-/// 2) additional "output" point, such that sum of inputs + output = 0
-/// 3) a regular function which vanishes exactly in all inputs and output
+/// this struct holds the following data, for some collection of "input" points which is not stored:
+/// 1) additional "output" point, such that sum of inputs + output = 0
+/// 2) a regular function which vanishes exactly in all inputs and output
 /// they can be merged together by composing outputs
 pub struct Propagation<C: CurveExt>
 where
     C::Base: FftPrecomp,
 {
-    inputs: Vec<C>,
     output: C,
     wtns: RegularFunction<C>,
 }
@@ -372,7 +1428,6 @@ where
             return Self::empty();
         }
         Propagation {
-            inputs: vec![pt],
             output: -pt,
             wtns: linefunc(&pt, &(-pt)),
         }
@@ -380,7 +1435,6 @@ where
 
     pub fn empty() -> Self {
         Propagation {
-            inputs: vec![],
             output: C::identity(),
             wtns: RegularFunction {
                 a: Polynomial::new(vec![C::Base::ONE]),
@@ -389,80 +1443,104 @@ where
         }
     }
 
+    /// the point repeated m times, computed by double-and-add, i.e. with O(log m) merges
+    pub fn from_multiple(pt: C, m: usize) -> Result<Self, MsmWitnessError> {
+        let mut acc = Self::empty();
+        let mut dbl = Self::from_point(pt);
+        let mut m = m;
+        while m > 0 {
+            if m & 1 == 1 {
+                acc = Self::merge(acc, dbl.clone())?;
+            }
+            m >>= 1;
+            if m > 0 {
+                dbl = Self::merge(dbl.clone(), dbl)?;
+            }
+        }
+        Ok(acc)
+    }
+
     pub fn from_pair(pt1: C, pt2: C) -> Self {
         if pt1 == C::identity() {
             return Self::from_point(pt2);
         }
         Propagation {
-            inputs: vec![pt1, pt2],
             output: -(pt1 + pt2),
             wtns: linefunc(&pt1, &pt2),
         }
     }
 
-    pub fn merge(a: Self, b: Self) -> Self {
-        let inputs = a.inputs.into_iter().chain(b.inputs.into_iter()).collect();
+    /// with the sanity-check feature, fails with InexactDivision if one of the witnesses is corrupted
+    pub fn merge(a: Self, b: Self) -> Result<Self, MsmWitnessError> {
         let output = a.output + b.output;
 
-        let (ax, _, az) = a.output.jacobian_coordinates();
-        let (bx, _, bz) = b.output.jacobian_coordinates();
-
-        if az.is_zero_vartime() || bz.is_zero_vartime() {
-            return Propagation {
-                inputs,
-                output,
-                wtns: &a.wtns * &b.wtns,
-            };
-        }
+        // no inversions here if the outputs are normalized, see group_merge
+        let (ax, bx) = match (affine_coords(&a.output), affine_coords(&b.output)) {
+            (Some((ax, _)), Some((bx, _))) => (ax, bx),
+            _ => {
+                return Ok(Propagation {
+                    output,
+                    wtns: &a.wtns * &b.wtns,
+                });
+            }
+        };
 
-        let numerator = &a.wtns * &(&b.wtns * &linefunc(&(-a.output), &(-b.output)));
+        let line = linefunc(&(-a.output), &(-b.output));
+        let numerator = RegularFunction::product(&[&a.wtns, &b.wtns, &line]);
 
         let num_a = numerator.a;
         let num_b = numerator.b;
 
-        let azinv = az.invert().unwrap();
-        let bzinv = bz.invert().unwrap();
-
-        let ax = ax * azinv * azinv;
-        let bx = bx * bzinv * bzinv;
-
+        // the numerator vanishes in both outputs, so the divisions are exact unless the witness is corrupted
+        #[cfg(feature = "sanity-check")]
+        let wtns = {
+            let div = |p: &Polynomial<C::Base>| p.kate_div_exact(ax)?.kate_div_exact(bx);
+            RegularFunction::new(div(&num_a)?, div(&num_b)?)
+        };
+        #[cfg(not(feature = "sanity-check"))]
         let wtns = RegularFunction::new(
             num_a.kate_div(ax).kate_div(bx),
             num_b.kate_div(ax).kate_div(bx),
         );
 
-        Propagation {
-            inputs,
+        Ok(Propagation {
             output: output,
             wtns,
-        }
+        })
     }
 
-    pub fn maybe_merge(m: MaybePair<C>) -> Self {
+    pub fn maybe_merge(m: MaybePair<C>) -> Result<Self, MsmWitnessError> {
         match m {
-            MaybePair::Unit(x) => x,
+            MaybePair::Unit(x) => Ok(x),
             MaybePair::Pair(x, y) => Self::merge(x, y),
         }
     }
 
     pub fn update_mpair_vec(pairs: &mut Vec<MaybePair<C>>, upd: Self) -> () {
-        let l = pairs.len();
-        if l == 0 {
-            pairs.push(MaybePair::Unit(upd))
-        } else {
-            match &pairs[l - 1] {
-                MaybePair::Pair(..) => pairs.push(MaybePair::Unit(upd)),
-                MaybePair::Unit(x) => pairs[l - 1] = MaybePair::Pair(x.clone(), upd),
+        // the last unit is moved out and paired, nothing is cloned
+        match pairs.pop() {
+            None => pairs.push(MaybePair::Unit(upd)),
+            Some(MaybePair::Unit(x)) => pairs.push(MaybePair::Pair(x, upd)),
+            Some(pair) => {
+                pairs.push(pair);
+                pairs.push(MaybePair::Unit(upd));
             }
         }
     }
 
-    pub fn group_merge(arr: Vec<Self>) -> Self {
+    pub fn group_merge(mut arr: Vec<Self>) -> Result<Self, MsmWitnessError> {
         if arr.len() == 0 {
-            panic!()
+            return Err(MsmWitnessError::EmptyInput);
         };
         if arr.len() == 1 {
-            return arr[0].clone();
+            return Ok(arr.into_iter().next().unwrap());
+        }
+
+        // a single batch inversion per level instead of two inversions per merge
+        let mut outputs: Vec<C> = arr.iter().map(|p| p.output).collect();
+        batch_normalize(&mut outputs);
+        for (p, output) in arr.iter_mut().zip(outputs) {
+            p.output = output;
         }
 
         let mut pairs = vec![];
@@ -474,7 +1552,8 @@ where
         parallelize(&mut tmp, |chunk, _| {
             for x in chunk.iter_mut() {
                 let store;
-                let tmp = x.clone();
+                // take the pair out instead of cloning it
+                let tmp = std::mem::replace(x, MaybePairGlue::Out(Ok(Self::empty())));
                 match tmp {
                     MaybePairGlue::In(m) => {
                         let tmp = Self::maybe_merge(m);
@@ -492,7 +1571,7 @@ where
                     MaybePairGlue::Out(p) => p,
                     _ => panic!(),
                 })
-                .collect(),
+                .collect::<Result<_, _>>()?,
         )
     }
 }
@@ -513,7 +1592,7 @@ where
     C::Base: FftPrecomp,
 {
     In(MaybePair<C>),
-    Out(Propagation<C>),
+    Out(Result<Propagation<C>, MsmWitnessError>),
 }
 
 /// computes projective coordinates from Jacobi coordinates
@@ -524,6 +1603,41 @@ pub fn projective_coords<C: CurveExt>(pt: &C) -> (C::Base, C::Base, C::Base) {
     (x * z, y, z * zsq)
 }
 
+/// computes affine coordinates from Jacobi coordinates, returns None for the identity;
+/// doesn't invert anything for points normalized by batch_normalize
+pub fn affine_coords<C: CurveExt>(pt: &C) -> Option<(C::Base, C::Base)> {
+    let (x, y, z) = pt.jacobian_coordinates();
+    if z == C::Base::ONE {
+        return Some((x, y));
+    }
+    let zinv: Option<C::Base> = z.invert().into();
+    zinv.map(|zinv| {
+        let zinvsq = zinv * zinv;
+        (x * zinvsq, y * zinvsq * zinv)
+    })
+}
+
+/// Checks that the curve is y^2 = x^3 + ax + b with a, b given by C::a() and C::b(), which all the divisor
+/// computations assume: the generator has to satisfy the equation, and the discriminant 4a^3 + 27b^2 can't vanish.
+pub fn check_short_weierstrass<C: CurveExt>() -> Result<(), MsmWitnessError> {
+    let (x, y) = affine_coords(&C::generator()).ok_or(MsmWitnessError::NotShortWeierstrass)?;
+    let (a, b) = (C::a(), C::b());
+    let disc = C::Base::from(4) * a * a * a + C::Base::from(27) * b * b;
+    if y * y != x * x * x + a * x + b || disc.is_zero_vartime() {
+        return Err(MsmWitnessError::NotShortWeierstrass);
+    }
+    Ok(())
+}
+
+/// brings the points to z = 1 (keeping the identity) with a single batch inversion, so that affine_coords is free
+pub fn batch_normalize<C: CurveExt>(pts: &mut [C]) {
+    let mut affine = vec![C::AffineExt::identity(); pts.len()];
+    C::batch_normalize(pts, &mut affine);
+    for (pt, a) in pts.iter_mut().zip(affine) {
+        *pt = a.into();
+    }
+}
+
 // utility functions for testing
 
 pub fn display_felt<F: PrimeField>(val: F) -> String {
@@ -539,27 +1653,129 @@ pub fn gen_random_pt<C: CurveExt>() -> C {
     hasher(&tmp.to_le_bytes())
 }
 
-pub fn compute_divisor_witness_partial<C: CurveExt>(pts: &[C]) -> (RegularFunction<C>, C)
+pub fn compute_divisor_witness_partial<C: CurveExt>(
+    pts: &[C],
+) -> Result<(RegularFunction<C>, C), MsmWitnessError>
+where
+    C::Base: FftPrecomp,
+{
+    check_short_weierstrass::<C>()?;
+    divisor_witness_partial(pts)
+}
+
+/// compute_divisor_witness_partial without the curve check
+fn divisor_witness_partial<C: CurveExt>(
+    pts: &[C],
+) -> Result<(RegularFunction<C>, C), MsmWitnessError>
 where
     C::Base: FftPrecomp,
 {
-    let mut tmp = vec![];
     if pts.len() == 0 {
-        return (RegularFunction::from_const(C::Base::ONE), C::identity());
+        return Ok((RegularFunction::from_const(C::Base::ONE), C::identity()));
+    }
+
+    let ret = Propagation::group_merge(propagation_leaves(pts)?)?;
+    Ok((ret.wtns, ret.output))
+}
+
+/// Groups repeated points, each of which becomes a single leaf built by Propagation::from_multiple, and splits the
+/// remaining points into pairs (and possibly one single point). The identity is skipped.
+/// Never returns an empty vector, so that the result can be passed to group_merge.
+/// This is the only place the input points are normalized, with a single batch inversion.
+fn propagation_leaves<C: CurveExt>(pts: &[C]) -> Result<Vec<Propagation<C>>, MsmWitnessError>
+where
+    C::Base: FftPrecomp,
+{
+    let mut normalized = pts.to_vec();
+    batch_normalize(&mut normalized);
+
+    let mut index: HashMap<Vec<u8>, usize> = HashMap::new();
+    let mut counts: Vec<(C, usize)> = vec![];
+    for pt in normalized {
+        let (x, y) = match affine_coords(&pt) {
+            Some(xy) => xy,
+            None => continue,
+        };
+        let key = [x.to_repr().as_ref(), y.to_repr().as_ref()].concat();
+        match index.entry(key) {
+            Entry::Occupied(e) => counts[*e.get()].1 += 1,
+            Entry::Vacant(e) => {
+                e.insert(counts.len());
+                counts.push((pt, 1));
+            }
+        }
     }
-    let mut i = 0;
-    while i < pts.len() - 1 {
-        let a = pts[i];
-        let b = pts[i + 1];
-        tmp.push(Propagation::from_pair(a, b));
-        i += 2
+
+    if counts.is_empty() {
+        return Ok(vec![Propagation::empty()]);
     }
-    if i == pts.len() - 1 {
-        tmp.push(Propagation::from_point(pts[i]))
+    let singles: Vec<C> = counts
+        .iter()
+        .filter(|(_, m)| *m == 1)
+        .map(|(pt, _)| *pt)
+        .collect();
+    counts
+        .iter()
+        .filter(|(_, m)| *m > 1)
+        .map(|(pt, m)| Propagation::from_multiple(*pt, *m))
+        .chain(singles.chunks(2).map(|c| match c {
+            [a, b] => Ok(Propagation::from_pair(*a, *b)),
+            _ => Ok(Propagation::from_point(c[0])),
+        }))
+        .collect()
+}
+
+/// Computes a regular function vanishing in the points of an iterator, fails if the sum is nonzero. The points are
+/// consumed in chunks of chunk_size, each chunk is merged in parallel, and the chunk results are merged like carries
+/// of a binary counter, so the points are never held in memory all at once and at most log(n / chunk_size) partial
+/// witnesses are alive, taking at most twice the size of the final one.
+pub fn compute_divisor_witness_streaming<C: CurveExt, I: IntoIterator<Item = C>>(
+    pts: I,
+    chunk_size: usize,
+) -> Result<RegularFunction<C>, MsmWitnessError>
+where
+    C::Base: FftPrecomp,
+{
+    check_short_weierstrass::<C>()?;
+    let mut pts = pts.into_iter();
+    // levels[i] covers 2^i chunks
+    let mut levels: Vec<Option<Propagation<C>>> = vec![];
+    loop {
+        let chunk: Vec<C> = pts.by_ref().take(chunk_size.max(1)).collect();
+        if chunk.len() == 0 {
+            break;
+        }
+        let mut acc = Propagation::group_merge(propagation_leaves(&chunk)?)?;
+        drop(chunk);
+
+        let mut i = 0;
+        loop {
+            if i == levels.len() {
+                levels.push(None);
+            }
+            match levels[i].take() {
+                None => {
+                    levels[i] = Some(acc);
+                    break;
+                }
+                Some(p) => {
+                    acc = Propagation::merge(p, acc)?;
+                    i += 1;
+                }
+            }
+        }
     }
 
-    let ret = Propagation::group_merge(tmp);
-    (ret.wtns, ret.output)
+    let mut levels = levels.into_iter().flatten();
+    let mut ret = levels.next().unwrap_or_else(Propagation::empty);
+    for p in levels {
+        ret = Propagation::merge(p, ret)?;
+    }
+    if ret.output != C::identity() {
+        // output is minus the sum of the points
+        return Err(nonzero_sum_error(-ret.output));
+    }
+    Ok(ret.wtns)
 }
 
 /// computes a regular function vanishing in a collection of points and minus their sum
@@ -568,16 +1784,151 @@ where
 //     (tmp.wtns, tmp.output)
 // }
 
-/// computes a regular function vanishing in a collection of points, panics if the sum is nonzero
-pub fn compute_divisor_witness<C: CurveExt>(pts: &[C]) -> RegularFunction<C>
+/// error reporting that the points sum to residual instead of the identity
+pub fn nonzero_sum_error<C: CurveExt>(residual: C) -> MsmWitnessError {
+    MsmWitnessError::NonZeroSum {
+        residual: match affine_coords(&residual) {
+            None => "identity".to_string(),
+            Some((x, y)) => format!("({}, {})", display_felt(x), display_felt(y)),
+        },
+    }
+}
+
+/// computes a regular function vanishing in a collection of points, fails if the sum is nonzero;
+/// a point repeated m times is a zero of order m, computed with O(log m) merges by Propagation::from_multiple
+pub fn compute_divisor_witness<C: CurveExt>(
+    pts: &[C],
+) -> Result<RegularFunction<C>, MsmWitnessError>
+where
+    C::Base: FftPrecomp,
+{
+    check_short_weierstrass::<C>()?;
+    compute_divisor_witness_unchecked(pts)
+}
+
+/// compute_divisor_witness without the curve check, for callers computing many divisors which run
+/// check_short_weierstrass once, as compute_lhs_witness does.
+pub fn compute_divisor_witness_unchecked<C: CurveExt>(
+    pts: &[C],
+) -> Result<RegularFunction<C>, MsmWitnessError>
 where
     C::Base: FftPrecomp,
 {
-    let tmp = compute_divisor_witness_partial(pts);
+    let tmp = divisor_witness_partial(pts)?;
     if tmp.1 != C::identity() {
-        panic!()
+        // output is minus the sum of the points
+        return Err(nonzero_sum_error(-tmp.1));
+    }
+    Ok(tmp.0)
+}
+
+/// Computes a regular function vanishing in a collection of points by solving directly for the coefficients of a and b,
+/// with degrees bounded as in RegularFunction::degree_bounds, using Gaussian elimination. It is cubic in the amount
+/// of points, so only competitive for small inputs, but is an independent check of compute_divisor_witness.
+/// Repeated points must be zeros of higher order: in a point (x0, y0) the function is expanded in t = x - x0,
+/// with y(t) = sqrt(x^3 + ax + b) as a power series, which is why points of order 2 can't be repeated.
+pub fn compute_divisor_witness_linalg<C: CurveExt>(
+    pts: &[C],
+) -> Result<RegularFunction<C>, MsmWitnessError>
+where
+    C::Base: FftPrecomp,
+{
+    check_short_weierstrass::<C>()?;
+    let sum = pts.iter().fold(C::identity(), |acc, x| acc + x);
+    if sum != C::identity() {
+        return Err(nonzero_sum_error(sum));
+    }
+
+    let mut mults: Vec<((C::Base, C::Base), usize)> = vec![];
+    for pt in pts.iter().filter_map(affine_coords) {
+        match mults.iter_mut().find(|(x, _)| *x == pt) {
+            Some((_, m)) => *m += 1,
+            None => mults.push((pt, 1)),
+        }
+    }
+    let n: usize = mults.iter().map(|(_, m)| m).sum();
+    if n == 0 {
+        return Ok(RegularFunction::from_const(C::Base::ONE));
     }
-    tmp.0
+
+    // columns are coefficients of a, then of b
+    let (len_a, len_b) = RegularFunction::<C>::num_coeffs(n);
+    let width = len_a + len_b;
+    let mut rows: Vec<Vec<C::Base>> = vec![];
+    for ((x0, y0), m) in mults {
+        if m > 1 && y0 == C::Base::ZERO {
+            return Err(MsmWitnessError::RepeatedTwoTorsion);
+        }
+        // y(t) mod t^m from y^2 = g(x0 + t) = g(x0) + g'(x0) t + 3 x0 t^2 + t^3
+        let g = [
+            y0 * y0,
+            C::Base::from(3) * x0 * x0 + C::a(),
+            C::Base::from(3) * x0,
+            C::Base::ONE,
+        ];
+        let inv = (y0 + y0).invert().unwrap_or(C::Base::ZERO);
+        let mut y = vec![y0];
+        for k in 1..m {
+            let conv = (1..k).fold(C::Base::ZERO, |acc, i| acc + y[i] * y[k - i]);
+            y.push((g.get(k).copied().unwrap_or(C::Base::ZERO) - conv) * inv);
+        }
+        // shifted[i] = (x0 + t)^i mod t^m
+        let mut shifted = vec![];
+        let mut acc: Vec<C::Base> = repeat(C::Base::ZERO).take(m).collect();
+        acc[0] = C::Base::ONE;
+        for _ in 0..cmp::max(len_a, len_b) {
+            let next = (0..m)
+                .map(|k| acc[k] * x0 + if k > 0 { acc[k - 1] } else { C::Base::ZERO })
+                .collect();
+            shifted.push(acc);
+            acc = next;
+        }
+        for k in 0..m {
+            let mut row: Vec<C::Base> = shifted[..len_a].iter().map(|c| c[k]).collect();
+            row.extend(
+                shifted[..len_b]
+                    .iter()
+                    .map(|c| (0..=k).fold(C::Base::ZERO, |acc, l| acc + y[l] * c[k - l])),
+            );
+            rows.push(row);
+        }
+    }
+
+    let mut pivots = vec![];
+    for col in 0..width {
+        let r = pivots.len();
+        if let Some(i) = (r..rows.len()).find(|i| rows[*i][col] != C::Base::ZERO) {
+            rows.swap(r, i);
+            let inv = rows[r][col].invert().unwrap();
+            rows[r].iter_mut().for_each(|x| *x *= inv);
+            for i in 0..rows.len() {
+                if i != r && rows[i][col] != C::Base::ZERO {
+                    let c = rows[i][col];
+                    let pivot_row = rows[r].clone();
+                    rows[i]
+                        .iter_mut()
+                        .zip(pivot_row)
+                        .for_each(|(x, y)| *x -= c * y);
+                }
+            }
+            pivots.push(col);
+        }
+    }
+    // the space of functions with this divisor is one-dimensional
+    let free: Vec<usize> = (0..width).filter(|c| !pivots.contains(c)).collect();
+    if free.len() != 1 {
+        return Err(MsmWitnessError::NotPrincipal);
+    }
+    let mut coeffs: Vec<C::Base> = repeat(C::Base::ZERO).take(width).collect();
+    coeffs[free[0]] = C::Base::ONE;
+    for (r, col) in pivots.into_iter().enumerate() {
+        coeffs[col] = -rows[r][free[0]];
+    }
+    let b = coeffs.split_off(len_a);
+    Ok(RegularFunction::new(
+        Polynomial::new(coeffs),
+        Polynomial::new(b),
+    ))
 }
 
 /// a collection of numerator and denominator lines
@@ -589,6 +1940,62 @@ where
     neg: Vec<RegularFunction<C>>,
 }
 
+impl<C: CurveExt> Arrangement<C>
+where
+    C::Base: FftPrecomp,
+{
+    /// numerator lines
+    pub fn pos(&self) -> &[RegularFunction<C>] {
+        &self.pos
+    }
+
+    /// denominator lines
+    pub fn neg(&self) -> &[RegularFunction<C>] {
+        &self.neg
+    }
+
+    /// Evaluates product(pos) / product(neg) in pt. Returns None for the identity, or if some denominator line
+    /// vanishes in pt, even if the zero cancels with a numerator line.
+    pub fn evaluate(&self, pt: C) -> Option<C::Base> {
+        let (x, y) = affine_coords(&pt)?;
+        let num = self
+            .pos
+            .iter()
+            .fold(C::Base::ONE, |acc, l| acc * l.ev_unchecked(x, y));
+        let den = self
+            .neg
+            .iter()
+            .fold(C::Base::ONE, |acc, l| acc * l.ev_unchecked(x, y));
+        Option::<C::Base>::from(den.invert()).map(|den| num * den)
+    }
+
+    /// Multiplies out the lines into a single regular function equal to product(pos) / product(neg).
+    /// The denominator is removed by multiplying by its conjugate, which turns it into a polynomial in x.
+    /// Fails with InexactDivision if the lines don't form a regular function, i.e. the arrangement is corrupted.
+    pub fn into_regular_function(self) -> Result<RegularFunction<C>, MsmWitnessError> {
+        let neg = Self::product(&self.neg);
+        let num = &Self::product(&self.pos) * &neg.conjugate();
+        let den = &neg * &neg.conjugate();
+        let div = |p: &Polynomial<C::Base>| {
+            let (q, r) = p.div_rem(&den.a);
+            if !r.is_zero() {
+                return Err(MsmWitnessError::InexactDivision);
+            }
+            Ok(q)
+        };
+        Ok(RegularFunction::new(div(&num.a)?, div(&num.b)?))
+    }
+
+    /// product of the functions, multiplied as a balanced tree
+    fn product(fs: &[RegularFunction<C>]) -> RegularFunction<C> {
+        match fs.len() {
+            0 => RegularFunction::from_const(C::Base::ONE),
+            1 => fs[0].clone(),
+            l => &Self::product(&fs[..l / 2]) * &Self::product(&fs[l / 2..]),
+        }
+    }
+}
+
 pub enum Glue<C: CurveExt>
 where
     C::Base: FftPrecomp,
@@ -612,10 +2019,13 @@ where
     }
 }
 
-pub fn compute_divisor_witness_naive<C: CurveExt>(pts: &[C]) -> Arrangement<C>
+pub fn compute_divisor_witness_naive<C: CurveExt>(
+    pts: &[C],
+) -> Result<Arrangement<C>, MsmWitnessError>
 where
     C::Base: FftPrecomp,
 {
+    check_short_weierstrass::<C>()?;
     let mut pos = pts.to_vec();
     let mut neg = vec![];
 
@@ -667,21 +2077,25 @@ where
     }
 
     if pos.len() == 0 && neg.len() == 0 {
-        return ret;
+        return Ok(ret);
     }
     if pos.len() == 1 && neg.len() == 0 {
-        assert!(pos[0] == C::identity());
-        return ret;
+        if pos[0] != C::identity() {
+            return Err(nonzero_sum_error(pos[0]));
+        }
+        return Ok(ret);
     }
     if pos.len() == 0 && neg.len() == 1 {
-        assert!(neg[0] == C::identity());
-        return ret;
+        if neg[0] != C::identity() {
+            return Err(nonzero_sum_error(-neg[0]));
+        }
+        return Ok(ret);
     }
-    if pos.len() == 1 && neg.len() == 1 {
-        assert!(pos[0] == neg[0]);
-        return ret;
+    // sum of pos minus sum of neg is preserved and equals the sum of inputs
+    if pos[0] != neg[0] {
+        return Err(nonzero_sum_error(pos[0] - neg[0]));
     }
-    panic!();
+    Ok(ret)
 }
 
 #[test]
@@ -706,7 +2120,39 @@ fn poly_test() {
     let r = Polynomial::new(repeat(F::ZERO).take(5).collect());
     let s = r.kate_div(t);
 
-    assert_eq!(s.poly.len(), 4); // checking that division does not pad leading zeros
+    assert!(s.is_zero()); // checking that division does not pad leading zeros
+
+    assert!(p.kate_div_exact(t).is_err());
+    assert_eq!(p2.kate_div_exact(t).unwrap().ev(t2), q.ev(t2));
+}
+
+#[test]
+
+fn div_rem_test() {
+    let p = Polynomial::new((0..100).map(|_| F::random(OsRng)).collect());
+    let d = Polynomial::new(
+        (0..17)
+            .map(|_| F::random(OsRng))
+            .chain(repeat(F::ZERO).take(3))
+            .collect(),
+    );
+    let (q, r) = p.div_rem(&d);
+    assert_eq!(q.poly.len(), 84);
+    assert_eq!(r.poly.len(), 16);
+
+    let t = F::random(OsRng);
+    assert_eq!(p.ev(t), q.ev(t) * d.ev(t) + r.ev(t));
+
+    let (q, r) = d.div_rem(&p);
+    assert!(q.poly.is_empty());
+    assert_eq!(r.ev(t), d.ev(t));
+
+    let g = p.inv_series(50).unwrap();
+    assert!((&p * &g).truncated(50) == Polynomial::new(vec![F::ONE]));
+    assert!(matches!(
+        (&p * &poly([F::ZERO, F::ONE])).inv_series(50),
+        Err(MsmWitnessError::NotInvertible)
+    ));
 }
 
 #[test]
@@ -777,6 +2223,101 @@ fn bench_best() {
 
 #[test]
 
+fn mul_strategy_test() {
+    let strategies = [
+        MulStrategy::Naive,
+        MulStrategy::Karatsuba,
+        MulStrategy::Fft,
+        MulStrategy::Auto,
+    ];
+    for (l1, l2) in [(100, 37), (20, 1000), (64, 64), (1, 300), (0, 10)] {
+        let p = Polynomial::new((0..l1).map(|_| F::random(OsRng)).collect());
+        let q = Polynomial::new((0..l2).map(|_| F::random(OsRng)).collect());
+        let expected = Polynomial::mul_naive(&p, &q);
+        for strategy in strategies {
+            assert!(p.mul_with(&q, strategy) == expected);
+        }
+    }
+
+    let t = MulThresholds {
+        karatsuba: 16,
+        fft: 128,
+    };
+    assert_eq!(t.pick(15), MulStrategy::Naive);
+    assert_eq!(t.pick(16), MulStrategy::Karatsuba);
+    assert_eq!(t.pick(200), MulStrategy::Fft);
+    assert_eq!(MulThresholds::default().pick(32), MulStrategy::Fft);
+}
+
+#[test]
+
+fn polynomial_eval_test() {
+    let p = Polynomial::new((0..100).map(|_| F::random(OsRng)).collect());
+    let q = Polynomial::new((0..50).map(|_| F::random(OsRng)).collect());
+    let r = Polynomial::new((0..20).map(|_| F::random(OsRng)).collect());
+
+    let log_size = PolynomialEval::<F>::log_size_for(149);
+    assert_eq!(log_size, 8);
+    let (pe, qe, re) = (
+        PolynomialEval::new(&p, log_size),
+        PolynomialEval::new(&q, log_size),
+        PolynomialEval::new(&r, log_size),
+    );
+    assert!((&(&pe * &qe) - &re).into_poly() == &(&p * &q) - &r);
+    assert!(pe.into_poly() == p);
+
+    let f: RegularFunction<Grumpkin> = RegularFunction::new(p.clone(), q.clone());
+    let g: RegularFunction<Grumpkin> = RegularFunction::new(q, r.clone());
+    let h: RegularFunction<Grumpkin> = RegularFunction::new(r, p);
+    let expected = f.mul_coeffs(&g).mul_coeffs(&h);
+    assert_eq!(
+        expected.pole_order(),
+        f.pole_order() + g.pole_order() + h.pole_order()
+    );
+    // == only compares up to a constant factor, so check the coefficients
+    let coeffs = |f: RegularFunction<Grumpkin>| (f.a, f.b);
+    assert!(coeffs(RegularFunction::product(&[&f, &g, &h])) == coeffs(expected));
+    assert!(coeffs(&f * &g) == coeffs(f.mul_coeffs(&g)));
+    assert!(coeffs(RegularFunction::product(&[&f])) == coeffs(f.clone()));
+    assert!(RegularFunction::<Grumpkin>::product(&[]) == RegularFunction::from_const(F::ONE));
+}
+
+#[test]
+#[ignore = "benchmark, run with --release -- --ignored"]
+
+fn calibrate_mul_test() {
+    let t = calibrate_mul_thresholds::<F>();
+    for threshold in [t.karatsuba, t.fft] {
+        assert!(threshold == usize::MAX || (threshold.is_power_of_two() && threshold <= 1024));
+    }
+    println!("karatsuba from {}, fft from {}", t.karatsuba, t.fft);
+
+    let old = mul_thresholds();
+    let t = calibrate_and_store_mul_thresholds::<F>();
+    assert_eq!(mul_thresholds(), t);
+    set_mul_thresholds(old);
+}
+
+#[test]
+
+fn batch_normalize_test() {
+    let mut pts: Vec<Grumpkin> = (0..10)
+        .map(|_| gen_random_pt::<Grumpkin>().double())
+        .collect();
+    pts[4] = Grumpkin::identity();
+    let orig = pts.clone();
+    batch_normalize(&mut pts);
+
+    for (pt, orig) in pts.iter().zip(orig.iter()) {
+        assert!(pt == orig);
+        assert_eq!(affine_coords(pt), affine_coords(orig));
+    }
+    assert!(pts[0].jacobian_coordinates().2 == F::ONE);
+    assert!(affine_coords(&pts[4]).is_none());
+}
+
+#[test]
+
 fn linefunc_test() {
     let pt1 = gen_random_pt::<Grumpkin>();
     let pt2 = gen_random_pt::<Grumpkin>();
@@ -790,6 +2331,64 @@ fn linefunc_test() {
 
 #[test]
 
+fn derivative_test() {
+    let p = Polynomial::new((0..10).map(|_| F::random(OsRng)).collect());
+    let q = Polynomial::new((0..7).map(|_| F::random(OsRng)).collect());
+    let t = F::random(OsRng);
+    assert_eq!(
+        (&p * &q).derivative().ev(t),
+        p.derivative().ev(t) * q.ev(t) + p.ev(t) * q.derivative().ev(t)
+    );
+
+    let f: RegularFunction<Grumpkin> = RegularFunction::new(p.clone(), q.clone());
+    let g: RegularFunction<Grumpkin> = RegularFunction::new(q, p);
+    let pt = gen_random_pt::<Grumpkin>();
+    assert_eq!(
+        (&f * &g).derivative().ev(pt),
+        f.derivative().ev(pt) * g.ev(pt) + f.ev(pt) * g.derivative().ev(pt)
+    ); // Leibniz rule on the curve
+}
+
+#[test]
+
+fn dlog_test() {
+    let pts: Vec<Grumpkin> = (0..4).map(|_| gen_random_pt()).collect();
+    let f = &linefunc(&pts[0], &pts[1]) * &linefunc(&pts[2], &pts[3]);
+    let zeros = [
+        pts[0],
+        pts[1],
+        -(pts[0] + pts[1]),
+        pts[2],
+        pts[3],
+        -(pts[2] + pts[3]),
+    ];
+
+    let a0 = gen_random_pt::<Grumpkin>();
+    let a1 = gen_random_pt::<Grumpkin>();
+    let (x0, y0) = affine_coords(&a0).unwrap();
+    let (x1, y1) = affine_coords(&a1).unwrap();
+    let slope = (y1 - y0) * (x1 - x0).invert().unwrap();
+    let mu = y0 - slope * x0;
+
+    let lhs = [a0, a1, -(a0 + a1)]
+        .into_iter()
+        .fold(F::ZERO, |acc, pt| acc + f.ev_dlog(pt, slope).unwrap());
+    let rhs = zeros.into_iter().fold(F::ZERO, |acc, pt| {
+        let (x, y) = affine_coords(&pt).unwrap();
+        acc - (y - slope * x - mu).invert().unwrap()
+    });
+    assert_eq!(lhs, rhs);
+
+    let many = f.ev_dlog_many(&[a0, a1, -(a0 + a1)], slope).unwrap();
+    assert_eq!(many.into_iter().fold(F::ZERO, |acc, v| acc + v), lhs);
+    assert!(matches!(
+        f.ev_dlog(zeros[0], slope),
+        Err(MsmWitnessError::DegenerateChallenge)
+    ));
+}
+
+#[test]
+
 fn randpoints_witness_test() {
     let mut scalars: Vec<Fq> = repeat(Fq::ONE).take(10000).collect();
     let mut pts: Vec<grumpkin::G1Affine> = repeat(gen_random_pt::<Grumpkin>().into())
@@ -800,12 +2399,13 @@ fn randpoints_witness_test() {
     scalars.push(Fq::ONE);
 
     let pts: Vec<Grumpkin> = pts.iter().map(|x| x.into()).collect();
-    let regf = compute_divisor_witness::<Grumpkin>(&pts);
+    let regf = compute_divisor_witness::<Grumpkin>(&pts).unwrap();
 
-    let _: Vec<()> = pts
+    assert!(regf
+        .ev_many(&pts)
+        .unwrap()
         .into_iter()
-        .map(|pt| assert!(regf.ev(pt.into()) == F::ZERO))
-        .collect();
+        .all(|x| x == F::ZERO));
 }
 
 #[test]
@@ -824,7 +2424,7 @@ fn witness_with_zeros_test() {
         a,
         -a,
     ];
-    let regf = compute_divisor_witness(&pts);
+    let regf = compute_divisor_witness(&pts).unwrap();
     let _: Vec<()> = pts
         .into_iter()
         .map(|pt| {
@@ -839,6 +2439,106 @@ fn witness_with_zeros_test() {
 
 #[test]
 
+fn tangent_test() {
+    let a = gen_random_pt::<Grumpkin>();
+    let t = tangentfunc(&a);
+    assert_eq!(t.vanishing_order(a), Some(2));
+    assert_eq!(t.vanishing_order(-(a + a)), Some(1));
+    assert_eq!(t.vanishing_order(-a), Some(0));
+    assert!(linefunc(&a, &a).same_divisor(&t));
+
+    let v = linefunc(&a, &-a);
+    assert_eq!(v.vanishing_order(a), Some(1));
+    assert_eq!(v.vanishing_order(-a), Some(1));
+    assert!(v.b.is_zero());
+
+    assert!(tangentfunc(&Grumpkin::identity()) == RegularFunction::from_const(F::ONE));
+}
+
+#[test]
+
+fn witness_multiplicity_test() {
+    let (a, b, c) = (
+        gen_random_pt::<Grumpkin>(),
+        gen_random_pt::<Grumpkin>(),
+        gen_random_pt::<Grumpkin>(),
+    );
+    let d = -(a * Fq::from(5) + b * Fq::from(3) + c);
+    let pts = vec![a, a, b, a, b, a, c, a, b, d];
+
+    let f = compute_divisor_witness(&pts).unwrap();
+    f.validate_degree_bounds(pts.len()).unwrap();
+    for (pt, m) in [(a, 5), (b, 3), (c, 1), (d, 1), (-a, 0), (-b, 0)] {
+        assert_eq!(f.vanishing_order(pt), Some(m));
+    }
+    assert!(f.same_divisor(&compute_divisor_witness_linalg(&pts).unwrap()));
+
+    for m in [1, 2, 3, 7, 16] {
+        let p = Propagation::from_multiple(a, m).unwrap();
+        assert!(p.output == -(a * Fq::from(m as u64)));
+        assert_eq!(p.wtns.vanishing_order(a), Some(m));
+        assert_eq!(p.wtns.vanishing_order(p.output), Some(1));
+        p.wtns.validate_degree_bounds(m + 1).unwrap();
+    }
+}
+
+#[test]
+
+fn two_torsion_test() {
+    use crate::test_curve::TorsionCurve;
+
+    let t = TorsionCurve::two_torsion();
+    assert!(bool::from(t.double().is_identity()));
+    let tan = tangentfunc(&t);
+    assert!(tan.b.is_zero());
+    assert_eq!(tan.vanishing_order(t), Some(2));
+    assert!(linefunc(&t, &-t).same_divisor(&tan));
+
+    for (m, order) in [(2, 2), (3, 4), (4, 4), (5, 6)] {
+        let p = Propagation::from_multiple(t, m).unwrap();
+        assert!(p.output == t * F::from(m as u64));
+        assert_eq!(p.wtns.vanishing_order(t), Some(order));
+        p.wtns.validate_degree_bounds(order).unwrap();
+    }
+
+    let (a, b) = (TorsionCurve::random(OsRng), TorsionCurve::random(OsRng));
+    assert!(bool::from(a.endo().is_on_curve()) && a.endo().endo() == -a);
+    let c = -(a + a + b + t);
+    let pts = vec![t, a, t, b, a, t, c];
+    let f = compute_divisor_witness(&pts).unwrap();
+    f.validate_degree_bounds(pts.len()).unwrap();
+    for (pt, m) in [(t, 3), (a, 2), (b, 1), (c, 1), (-a, 0)] {
+        assert_eq!(f.vanishing_order(pt), Some(m));
+    }
+}
+
+#[test]
+
+fn streaming_witness_test() {
+    let mut pts: Vec<Grumpkin> = (0..300).map(|_| gen_random_pt()).collect();
+    pts[17] = pts[16];
+    pts.insert(50, Grumpkin::identity());
+    pts.push(-pts.iter().fold(Grumpkin::identity(), |acc, x| acc + x));
+    let f = compute_divisor_witness(&pts).unwrap();
+
+    for chunk_size in [0, 1, 7, 64, 1000] {
+        let g = compute_divisor_witness_streaming(pts.iter().copied(), chunk_size).unwrap();
+        g.validate_degree_bounds(pts.len()).unwrap();
+        assert!(g.same_divisor(&f));
+    }
+
+    let g = compute_divisor_witness_streaming(std::iter::empty::<Grumpkin>(), 16).unwrap();
+    assert!(g == RegularFunction::from_const(F::ONE));
+
+    pts.pop();
+    assert!(matches!(
+        compute_divisor_witness_streaming(pts.into_iter(), 16),
+        Err(MsmWitnessError::NonZeroSum { .. })
+    ));
+}
+
+#[test]
+
 fn randpoints_witness_naive_test() {
     let mut scalars: Vec<Fq> = repeat(Fq::ONE).take(500).collect();
     let mut pts: Vec<Grumpkin> = repeat(gen_random_pt()).take(500).collect();
@@ -847,7 +2547,32 @@ fn randpoints_witness_naive_test() {
     pts.push(-res);
     scalars.push(Fq::ONE);
 
-    let _ = compute_divisor_witness_naive(&pts);
+    compute_divisor_witness_naive(&pts).unwrap();
+}
+
+#[test]
+
+fn arrangement_test() {
+    let mut pts: Vec<Grumpkin> = (0..100).map(|_| gen_random_pt()).collect();
+    pts[3] = pts[2];
+    pts.push(-pts.iter().fold(Grumpkin::identity(), |acc, x| acc + x));
+    let arr = compute_divisor_witness_naive(&pts).unwrap();
+    assert!(arr.pos().len() > 0 && arr.neg().len() > 0);
+
+    let q = gen_random_pt::<Grumpkin>();
+    let val = arr.evaluate(q).unwrap();
+    assert!(arr.evaluate(Grumpkin::identity()).is_none());
+
+    let f = arr.into_regular_function().unwrap();
+    assert_eq!(f.ev(q), val);
+    f.validate_degree_bounds(pts.len()).unwrap();
+    assert!(f.same_divisor(&compute_divisor_witness(&pts).unwrap()));
+
+    let g = compute_divisor_witness_naive::<Grumpkin>(&[])
+        .unwrap()
+        .into_regular_function()
+        .unwrap();
+    assert!(g == RegularFunction::from_const(F::ONE));
 }
 
 #[test]
@@ -861,14 +2586,21 @@ fn randpoints_witness_bench() {
     scalars.push(Fq::ONE);
 
     let start = SystemTime::now();
-    compute_divisor_witness(&pts);
+    compute_divisor_witness(&pts).unwrap();
     println!(
         "Computed regular function vanishing in 1024 random points in {} ms",
         start.elapsed().unwrap().as_millis()
     );
 
     let start = SystemTime::now();
-    compute_divisor_witness_naive(&pts);
+    compute_divisor_witness_streaming(pts.iter().copied(), 256).unwrap();
+    println!(
+        "Computed regular function vanishing in 1024 random points in chunks of 256 in {} ms",
+        start.elapsed().unwrap().as_millis()
+    );
+
+    let start = SystemTime::now();
+    compute_divisor_witness_naive(&pts).unwrap();
     println!(
         "Computed configuration of lines vanishing in 1024 random points in {} ms",
         start.elapsed().unwrap().as_millis()
@@ -908,10 +2640,322 @@ fn randpoints_witness_bench() {
 
     let start = SystemTime::now();
     for _ in 0..256 {
-        compute_divisor_witness(&pts);
+        compute_divisor_witness(&pts).unwrap();
     }
     println!(
         "Computed regular function vanishing in 4 random points 256 times in {} ms",
         start.elapsed().unwrap().as_millis()
     );
+
+    let start = SystemTime::now();
+    for _ in 0..256 {
+        compute_divisor_witness_linalg(&pts).unwrap();
+    }
+    println!(
+        "Computed regular function vanishing in 4 random points 256 times in {} ms using linear algebra",
+        start.elapsed().unwrap().as_millis()
+    );
+}
+
+#[test]
+
+fn witness_errors_test() {
+    let pts: Vec<Grumpkin> = (0..5).map(|_| gen_random_pt()).collect();
+    let sum = pts.iter().fold(Grumpkin::identity(), |acc, x| acc + x);
+    assert_eq!(
+        compute_divisor_witness(&pts).err(),
+        Some(nonzero_sum_error(sum))
+    );
+    assert!(matches!(
+        compute_divisor_witness_naive(&pts),
+        Err(MsmWitnessError::NonZeroSum { .. })
+    ));
+    assert!(matches!(
+        Propagation::<Grumpkin>::group_merge(vec![]),
+        Err(MsmWitnessError::EmptyInput)
+    ));
+
+    // a witness which doesn't vanish in its output
+    let mut corrupted = Propagation::from_point(pts[0]);
+    corrupted.wtns = RegularFunction::from_const(F::ONE);
+    let merged = Propagation::merge(corrupted, Propagation::from_point(pts[1]));
+    #[cfg(feature = "sanity-check")]
+    assert!(matches!(merged, Err(MsmWitnessError::InexactDivision)));
+    #[cfg(not(feature = "sanity-check"))]
+    assert!(merged.is_ok());
+
+    // a denominator line which doesn't cancel against the numerator
+    let mut corrupted =
+        compute_divisor_witness_naive(&[pts[0], pts[1], -(pts[0] + pts[1])]).unwrap();
+    corrupted.neg.push(linefunc(&pts[2], &pts[3]));
+    assert!(matches!(
+        corrupted.into_regular_function(),
+        Err(MsmWitnessError::InexactDivision)
+    ));
+}
+
+#[test]
+
+fn generic_fft_precomp_test() {
+    use halo2curves::pasta::{self, Fp as PallasBase};
+    use halo2curves::secp256k1::Fp as SecpBase;
+
+    for i in 0..=PallasBase::S {
+        assert!(PallasBase::omega_pow(i) == PallasBase::ROOT_OF_UNITY.pow([1u64 << i]));
+        assert!(PallasBase::omega_pow(i) * PallasBase::omega_pow_inv(i) == PallasBase::ONE);
+        assert!(PallasBase::half_pow(i as u64) == PallasBase::TWO_INV.pow([i as u64]));
+    }
+
+    let a = poly((0..100).map(|_| SecpBase::random(OsRng)));
+    let b = poly((0..100).map(|_| SecpBase::random(OsRng)));
+    let x = SecpBase::random(OsRng);
+    assert!((&a * &b).ev(x) == a.ev(x) * b.ev(x));
+
+    let mut pts: Vec<pasta::Ep> = (0..100).map(|_| gen_random_pt()).collect();
+    pts.push(-pts.iter().fold(pasta::Ep::identity(), |acc, x| acc + x));
+    let regf = compute_divisor_witness(&pts).unwrap();
+    for pt in pts {
+        assert!(regf.ev(pt) == PallasBase::ZERO);
+    }
+}
+
+#[test]
+
+fn short_weierstrass_test() {
+    use halo2curves::pasta;
+    use halo2curves::secp256r1::{Fp as P256Base, Fq as P256Scalar, Secp256r1};
+
+    check_short_weierstrass::<Grumpkin>().unwrap();
+    check_short_weierstrass::<pasta::Ep>().unwrap();
+    check_short_weierstrass::<Secp256r1>().unwrap();
+    assert!(Secp256r1::a() != P256Base::ZERO);
+
+    let gen = || Secp256r1::generator() * P256Scalar::random(OsRng);
+    let mut pts: Vec<Secp256r1> = (0..40).map(|_| gen()).collect();
+    pts[1] = pts[0];
+    pts[2] = -pts[3];
+    pts.push(-pts.iter().fold(Secp256r1::identity(), |acc, x| acc + x));
+
+    let f = compute_divisor_witness(&pts).unwrap();
+    f.validate_degree_bounds(pts.len()).unwrap();
+    assert!(f
+        .ev_many(&pts)
+        .unwrap()
+        .into_iter()
+        .all(|x| x == P256Base::ZERO));
+    assert_eq!(f.vanishing_order(pts[0]), Some(2));
+    assert_eq!(f.vanishing_order(gen()), Some(0));
+
+    assert!(f.same_divisor(&compute_divisor_witness_linalg(&pts).unwrap()));
+    assert!(f.same_divisor(&compute_divisor_witness_streaming(pts.iter().copied(), 8).unwrap()));
+    let arr = compute_divisor_witness_naive(&pts).unwrap();
+    assert!(f.same_divisor(&arr.into_regular_function().unwrap()));
+
+    let a = gen();
+    let t = tangentfunc(&a);
+    assert_eq!(t.vanishing_order(a), Some(2));
+    assert_eq!(t.vanishing_order(-(a + a)), Some(1));
+    assert!(linefunc(&a, &a).same_divisor(&t));
+
+    // Leibniz rule and the product need the a x term of the curve equation
+    let g = &linefunc(&a, &gen()) * &t;
+    let pt = gen();
+    assert_eq!(
+        (&f * &g).derivative().ev(pt),
+        f.derivative().ev(pt) * g.ev(pt) + f.ev(pt) * g.derivative().ev(pt)
+    );
+    assert_eq!((&f * &g).ev(pt), f.ev(pt) * g.ev(pt));
+}
+
+#[test]
+
+fn ops_test() {
+    let p = Polynomial::new((0..100).map(|_| F::random(OsRng)).collect());
+    let q = Polynomial::new((0..42).map(|_| F::random(OsRng)).collect());
+    let t = F::random(OsRng);
+    let sc = F::random(OsRng);
+
+    assert_eq!((&p - &q).ev(t), p.ev(t) - q.ev(t));
+    assert_eq!((&q - &p).ev(t), q.ev(t) - p.ev(t));
+    assert_eq!((-&p).ev(t), -p.ev(t));
+    assert_eq!((&p * sc).ev(t), p.ev(t) * sc);
+    assert_eq!((p.clone() + q.clone()).ev(t), p.ev(t) + q.ev(t));
+    assert_eq!((p.clone() * q.clone()).ev(t), p.ev(t) * q.ev(t));
+
+    let mut r = q.clone();
+    r += &p;
+    r -= &q;
+    r *= &q;
+    r *= sc;
+    assert_eq!(r.ev(t), p.ev(t) * q.ev(t) * sc);
+
+    let f: RegularFunction<Grumpkin> = RegularFunction::new(p.clone(), q.clone());
+    let g: RegularFunction<Grumpkin> = RegularFunction::new(q, p);
+    let pt = gen_random_pt::<Grumpkin>();
+    assert_eq!((&f - &g).ev(pt), f.ev(pt) - g.ev(pt));
+    assert_eq!((-&f).ev(pt), -f.ev(pt));
+    assert_eq!((f.clone() * g.clone()).ev(pt), f.ev(pt) * g.ev(pt));
+
+    let mut h = f.clone();
+    h += &g;
+    h *= &f;
+    h -= &g;
+    h.scale_assign(sc);
+    assert_eq!(h.ev(pt), ((f.ev(pt) + g.ev(pt)) * f.ev(pt) - g.ev(pt)) * sc);
+}
+
+#[test]
+
+fn degree_test() {
+    let mut p = Polynomial::new(vec![F::ONE, F::ZERO, F::from(3), F::ZERO, F::ZERO]);
+    assert_eq!(p.poly.len(), 3);
+    assert_eq!(p.degree(), Some(2));
+
+    p.poly.push(F::ZERO);
+    assert_eq!(p.degree(), Some(2));
+    assert_eq!(p.padded(3).unwrap(), vec![F::ONE, F::ZERO, F::from(3)]);
+    assert!(matches!(
+        p.padded(2),
+        Err(MsmWitnessError::DegreeBound {
+            degree: 2,
+            coeffs: 2
+        })
+    ));
+    p.trim();
+    assert_eq!(p.poly.len(), 3);
+
+    let q = poly([F::ZERO, F::ONE, -F::from(3)]);
+    assert_eq!((&p + &q).degree(), Some(1));
+    assert!((&p - &p).is_zero());
+    assert!((&p * &Polynomial::new(vec![])).is_zero());
+    assert_eq!((&p * F::ZERO).poly.len(), 0);
+    assert!(Polynomial::<F>::new(vec![F::ZERO]).degree().is_none());
+
+    let big = Polynomial::new((0..100).map(|_| F::random(OsRng)).collect());
+    let r = &(&big * &p) - &(&big * &q);
+    assert_eq!(r.degree(), Some(101));
+    assert_eq!(r.poly.len(), 102);
+}
+
+#[test]
+
+fn degree_bounds_test() {
+    for n in [2, 3, 4, 7, 10, 33] {
+        let mut pts: Vec<Grumpkin> = (0..n - 1).map(|_| gen_random_pt()).collect();
+        pts.push(-pts.iter().fold(Grumpkin::identity(), |acc, x| acc + x));
+        let regf = compute_divisor_witness(&pts).unwrap();
+        regf.validate_degree_bounds(n).unwrap();
+
+        let (a, b) = regf.padded_coeffs(n).unwrap();
+        assert_eq!(
+            (a.len(), b.len()),
+            RegularFunction::<Grumpkin>::num_coeffs(n)
+        );
+        let pt = gen_random_pt::<Grumpkin>();
+        assert_eq!(
+            RegularFunction::<Grumpkin>::new(Polynomial::new(a), Polynomial::new(b)).ev(pt),
+            regf.ev(pt)
+        );
+
+        assert!(matches!(
+            regf.validate_degree_bounds(n - 2),
+            Err(MsmWitnessError::DegreeBound { .. })
+        ));
+    }
+}
+
+#[test]
+
+fn ev_many_test() {
+    let p = Polynomial::new((0..1000).map(|_| F::random(OsRng)).collect());
+    let xs: Vec<F> = (0..777).map(|_| F::random(OsRng)).collect();
+    let expected: Vec<F> = xs.iter().map(|x| p.ev(*x)).collect();
+    assert!(p.ev_many(&xs) == expected);
+
+    let d = Polynomial::new((0..300).map(|_| F::random(OsRng)).collect());
+    let (q, r) = p.div_rem(&d);
+    assert_eq!(r.poly.len(), 299);
+    assert!((&(&q * &d) + &r).poly == p.poly);
+
+    let regf: RegularFunction<Grumpkin> = RegularFunction::new(p.clone(), d);
+    let pts: Vec<Grumpkin> = (0..200).map(|_| gen_random_pt()).collect();
+    let expected: Vec<F> = pts.iter().map(|pt| regf.ev(*pt)).collect();
+    assert!(regf.ev_many(&pts).unwrap() == expected);
+    assert_eq!(
+        regf.ev_many(&[pts[0], Grumpkin::identity()]),
+        Err(MsmWitnessError::DegenerateChallenge)
+    );
+}
+
+#[test]
+
+fn interpolation_test() {
+    let roots: Vec<F> = (0..300).map(|_| F::random(OsRng)).collect();
+    let m = Polynomial::from_roots(&roots);
+    assert_eq!(m.degree(), Some(300));
+    assert_eq!(m.poly[300], F::ONE);
+    assert!(m.ev_many(&roots).into_iter().all(|x| x == F::ZERO));
+
+    for n in [1, 10, 300] {
+        let p = Polynomial::new((0..n).map(|_| F::random(OsRng)).collect());
+        let pts: Vec<(F, F)> = roots[..n].iter().map(|x| (*x, p.ev(*x))).collect();
+        assert!(Polynomial::interpolate(&pts).unwrap().poly == p.poly);
+    }
+    assert!(Polynomial::<F>::interpolate(&[]).unwrap().is_zero());
+    assert!(matches!(
+        Polynomial::interpolate(&[(roots[0], F::ONE), (roots[0], F::ZERO)]),
+        Err(MsmWitnessError::DuplicateX)
+    ));
+}
+
+#[test]
+
+fn linalg_witness_test() {
+    let a = gen_random_pt::<Grumpkin>();
+    let b = gen_random_pt::<Grumpkin>();
+    let mut random: Vec<Grumpkin> = (0..30).map(|_| gen_random_pt()).collect();
+    random.push(-random.iter().fold(Grumpkin::identity(), |acc, x| acc + x));
+    let cases: Vec<Vec<Grumpkin>> = vec![
+        random,
+        vec![a, a, a, b, b, -(a * Fq::from(3) + b * Fq::from(2))],
+        vec![a, -a, Grumpkin::identity(), b, b, -b, -b],
+        vec![a, a, a, a, a, -(a * Fq::from(5))],
+    ];
+
+    for pts in cases {
+        let f = compute_divisor_witness_linalg(&pts).unwrap();
+        let g = compute_divisor_witness(&pts).unwrap();
+        f.validate_degree_bounds(pts.len()).unwrap();
+        assert!(!f.is_zero());
+        assert!(f.same_divisor(&g));
+    }
+
+    let pts: Vec<Grumpkin> = (0..5).map(|_| gen_random_pt()).collect();
+    assert!(matches!(
+        compute_divisor_witness_linalg(&pts),
+        Err(MsmWitnessError::NonZeroSum { .. })
+    ));
+}
+
+#[test]
+
+fn normalize_test() {
+    let mut pts: Vec<Grumpkin> = (0..10).map(|_| gen_random_pt()).collect();
+    pts.push(-pts.iter().fold(Grumpkin::identity(), |acc, x| acc + x));
+    let f = compute_divisor_witness(&pts).unwrap();
+    let sc = F::random(OsRng);
+
+    let g = f.normalize();
+    assert_eq!(*g.a.poly.last().unwrap(), F::ONE);
+    assert!(g == f.scale(sc).normalize());
+    assert!(f == f.scale(sc));
+    assert!(f.a != f.scale(sc).a);
+    assert!(f.same_divisor(&f.scale(sc)));
+
+    pts[0] = -pts[0];
+    pts[10] = pts[10] - pts[0] - pts[0];
+    assert!(!f.same_divisor(&compute_divisor_witness(&pts).unwrap()));
+
+    let y: RegularFunction<Grumpkin> = RegularFunction::new(poly([]), poly([sc]));
+    assert!(y.normalize() == RegularFunction::new(poly([]), poly([F::ONE])));
 }
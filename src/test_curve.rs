@@ -0,0 +1,517 @@
+//! A toy curve y^2 = x^3 + x over the bn256 scalar field, used only in tests. Unlike the curves from halo2curves
+//! its order is even, so it has points of order 2 such as (0, 0), which the divisor computations handle separately.
+//! Points are always kept affine (z is 0 or 1, the identity is (0, 1, 0)), nothing is constant time, and since the
+//! group order is unknown the "scalar field" is just the base field: scalars multiply by their integer representative.
+
+use halo2curves::bn256::Fr as F;
+use halo2curves::ff::{Field, PrimeField};
+use halo2curves::group::{
+    prime::{PrimeCurve, PrimeCurveAffine, PrimeGroup},
+    Curve, Group, GroupEncoding,
+};
+use halo2curves::{Coordinates, CurveAffine, CurveExt};
+use rand_core::RngCore;
+use std::{
+    iter::Sum,
+    ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign},
+};
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq, CtOption};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TorsionCurve {
+    x: F,
+    y: F,
+    z: F,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TorsionCurveAffine {
+    x: F,
+    y: F,
+    z: F,
+}
+
+fn rhs(x: F) -> F {
+    x * x * x + x
+}
+
+/// the point with abscissa x and the given parity of the ordinate, if there is one
+fn lift_x(x: F, odd: bool) -> CtOption<TorsionCurve> {
+    rhs(x).sqrt().map(|y| {
+        let y = if bool::from(y.is_odd()) == odd { y } else { -y };
+        TorsionCurve { x, y, z: F::ONE }
+    })
+}
+
+impl TorsionCurve {
+    /// the point (0, 0) of order 2
+    pub fn two_torsion() -> Self {
+        TorsionCurve {
+            x: F::ZERO,
+            y: F::ZERO,
+            z: F::ONE,
+        }
+    }
+
+    fn add_pts(&self, other: &Self) -> Self {
+        if self.z == F::ZERO {
+            return *other;
+        }
+        if other.z == F::ZERO {
+            return *self;
+        }
+        if self.x == other.x {
+            if self.y != other.y || self.y == F::ZERO {
+                return Self::identity();
+            }
+            return self.double();
+        }
+        let slope = (other.y - self.y) * (other.x - self.x).invert().unwrap();
+        self.complete(slope, other.x)
+    }
+
+    /// the sum of self and another point with abscissa x1 on the line of the given slope
+    fn complete(&self, slope: F, x1: F) -> Self {
+        let x = slope * slope - self.x - x1;
+        TorsionCurve {
+            x,
+            y: slope * (self.x - x) - self.y,
+            z: F::ONE,
+        }
+    }
+
+    fn mul_scalar(&self, scalar: &F) -> Self {
+        let mut acc = Self::identity();
+        for byte in scalar.to_repr().as_ref().iter().rev() {
+            for i in (0..8).rev() {
+                acc = acc.double();
+                if (byte >> i) & 1 == 1 {
+                    acc = acc.add_pts(self);
+                }
+            }
+        }
+        acc
+    }
+}
+
+impl Group for TorsionCurve {
+    type Scalar = F;
+
+    fn random(mut rng: impl RngCore) -> Self {
+        loop {
+            let pt = lift_x(F::random(&mut rng), rng.next_u32() & 1 == 1);
+            if pt.is_some().into() {
+                return pt.unwrap();
+            }
+        }
+    }
+
+    fn identity() -> Self {
+        TorsionCurve {
+            x: F::ZERO,
+            y: F::ONE,
+            z: F::ZERO,
+        }
+    }
+
+    /// the point with the smallest positive abscissa and even ordinate
+    fn generator() -> Self {
+        let mut x = F::ONE;
+        loop {
+            let pt = lift_x(x, false);
+            if pt.is_some().into() {
+                return pt.unwrap();
+            }
+            x += F::ONE;
+        }
+    }
+
+    fn is_identity(&self) -> Choice {
+        self.z.is_zero()
+    }
+
+    fn double(&self) -> Self {
+        if self.z == F::ZERO || self.y == F::ZERO {
+            return Self::identity();
+        }
+        let slope = (F::from(3) * self.x * self.x + F::ONE) * (self.y + self.y).invert().unwrap();
+        self.complete(slope, self.x)
+    }
+}
+
+impl Curve for TorsionCurve {
+    type AffineRepr = TorsionCurveAffine;
+
+    fn to_affine(&self) -> TorsionCurveAffine {
+        TorsionCurveAffine {
+            x: self.x,
+            y: self.y,
+            z: self.z,
+        }
+    }
+}
+
+impl GroupEncoding for TorsionCurve {
+    type Repr = TorsionCurveRepr;
+
+    fn from_bytes(bytes: &TorsionCurveRepr) -> CtOption<Self> {
+        let flags = bytes.0[32];
+        if flags & 2 == 2 {
+            return CtOption::new(Self::identity(), Choice::from(1));
+        }
+        let mut repr = <F as PrimeField>::Repr::default();
+        repr.as_mut().copy_from_slice(&bytes.0[..32]);
+        let x: Option<F> = F::from_repr(repr).into();
+        match x {
+            Some(x) => lift_x(x, flags & 1 == 1),
+            None => CtOption::new(Self::identity(), Choice::from(0)),
+        }
+    }
+
+    fn from_bytes_unchecked(bytes: &TorsionCurveRepr) -> CtOption<Self> {
+        Self::from_bytes(bytes)
+    }
+
+    fn to_bytes(&self) -> TorsionCurveRepr {
+        let mut ret = TorsionCurveRepr::default();
+        if self.z == F::ZERO {
+            ret.0[32] = 2;
+        } else {
+            ret.0[..32].copy_from_slice(self.x.to_repr().as_ref());
+            ret.0[32] = self.y.is_odd().unwrap_u8();
+        }
+        ret
+    }
+}
+
+impl PrimeGroup for TorsionCurve {}
+
+impl PrimeCurve for TorsionCurve {
+    type Affine = TorsionCurveAffine;
+}
+
+impl CurveExt for TorsionCurve {
+    type ScalarExt = F;
+    type Base = F;
+    type AffineExt = TorsionCurveAffine;
+
+    const CURVE_ID: &'static str = "torsion_test_curve";
+
+    /// (x, y) -> (-x, iy) with i^2 = -1, an automorphism of order 4 since the curve has j-invariant 1728
+    fn endo(&self) -> Self {
+        let i = (-F::ONE).sqrt().unwrap();
+        TorsionCurve {
+            x: -self.x,
+            y: i * self.y,
+            z: self.z,
+        }
+    }
+
+    fn jacobian_coordinates(&self) -> (F, F, F) {
+        (self.x, self.y, self.z)
+    }
+
+    /// try-and-increment from the first bytes of the message, only meant to be deterministic
+    fn hash_to_curve<'a>(_domain_prefix: &'a str) -> Box<dyn Fn(&[u8]) -> Self + 'a> {
+        Box::new(|msg| {
+            let mut seed = [0u8; 8];
+            for (s, m) in seed.iter_mut().zip(msg) {
+                *s = *m;
+            }
+            let mut x = F::from(u64::from_le_bytes(seed));
+            loop {
+                let pt = lift_x(x, false);
+                if pt.is_some().into() {
+                    return pt.unwrap();
+                }
+                x += F::ONE;
+            }
+        })
+    }
+
+    fn is_on_curve(&self) -> Choice {
+        Choice::from((self.z == F::ZERO || self.y * self.y == rhs(self.x)) as u8)
+    }
+
+    fn a() -> F {
+        F::ONE
+    }
+
+    fn b() -> F {
+        F::ZERO
+    }
+
+    fn new_jacobian(x: F, y: F, z: F) -> CtOption<Self> {
+        if z == F::ZERO {
+            return CtOption::new(Self::identity(), Choice::from(1));
+        }
+        let zinv = z.invert().unwrap();
+        let zinvsq = zinv * zinv;
+        let pt = TorsionCurve {
+            x: x * zinvsq,
+            y: y * zinvsq * zinv,
+            z: F::ONE,
+        };
+        CtOption::new(pt, pt.is_on_curve())
+    }
+}
+
+impl PrimeCurveAffine for TorsionCurveAffine {
+    type Scalar = F;
+    type Curve = TorsionCurve;
+
+    fn identity() -> Self {
+        TorsionCurve::identity().to_affine()
+    }
+
+    fn generator() -> Self {
+        TorsionCurve::generator().to_affine()
+    }
+
+    fn is_identity(&self) -> Choice {
+        self.z.is_zero()
+    }
+
+    fn to_curve(&self) -> TorsionCurve {
+        TorsionCurve {
+            x: self.x,
+            y: self.y,
+            z: self.z,
+        }
+    }
+}
+
+impl GroupEncoding for TorsionCurveAffine {
+    type Repr = TorsionCurveRepr;
+
+    fn from_bytes(bytes: &TorsionCurveRepr) -> CtOption<Self> {
+        TorsionCurve::from_bytes(bytes).map(|pt| pt.to_affine())
+    }
+
+    fn from_bytes_unchecked(bytes: &TorsionCurveRepr) -> CtOption<Self> {
+        Self::from_bytes(bytes)
+    }
+
+    fn to_bytes(&self) -> TorsionCurveRepr {
+        self.to_curve().to_bytes()
+    }
+}
+
+impl CurveAffine for TorsionCurveAffine {
+    type ScalarExt = F;
+    type Base = F;
+    type CurveExt = TorsionCurve;
+
+    fn coordinates(&self) -> CtOption<Coordinates<Self>> {
+        Coordinates::from_xy(self.x, self.y).and_then(|c| CtOption::new(c, !self.is_identity()))
+    }
+
+    fn from_xy(x: F, y: F) -> CtOption<Self> {
+        TorsionCurve::new_jacobian(x, y, F::ONE).map(|pt| pt.to_affine())
+    }
+
+    fn is_on_curve(&self) -> Choice {
+        self.to_curve().is_on_curve()
+    }
+
+    fn a() -> F {
+        F::ONE
+    }
+
+    fn b() -> F {
+        F::ZERO
+    }
+}
+
+/// x in little endian followed by a flag byte: the parity of y, or 2 for the identity
+#[derive(Clone, Copy)]
+pub struct TorsionCurveRepr([u8; 33]);
+
+impl Default for TorsionCurveRepr {
+    fn default() -> Self {
+        TorsionCurveRepr([0; 33])
+    }
+}
+
+impl AsRef<[u8]> for TorsionCurveRepr {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl AsMut<[u8]> for TorsionCurveRepr {
+    fn as_mut(&mut self) -> &mut [u8] {
+        &mut self.0
+    }
+}
+
+impl Default for TorsionCurve {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+impl Default for TorsionCurveAffine {
+    fn default() -> Self {
+        <Self as PrimeCurveAffine>::identity()
+    }
+}
+
+impl From<TorsionCurveAffine> for TorsionCurve {
+    fn from(pt: TorsionCurveAffine) -> Self {
+        pt.to_curve()
+    }
+}
+
+impl From<TorsionCurve> for TorsionCurveAffine {
+    fn from(pt: TorsionCurve) -> Self {
+        pt.to_affine()
+    }
+}
+
+macro_rules! impl_choice_traits {
+    ($t:ident) => {
+        impl ConditionallySelectable for $t {
+            fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+                $t {
+                    x: F::conditional_select(&a.x, &b.x, choice),
+                    y: F::conditional_select(&a.y, &b.y, choice),
+                    z: F::conditional_select(&a.z, &b.z, choice),
+                }
+            }
+        }
+
+        impl ConstantTimeEq for $t {
+            fn ct_eq(&self, other: &Self) -> Choice {
+                Choice::from((self == other) as u8)
+            }
+        }
+
+        impl Neg for $t {
+            type Output = $t;
+
+            fn neg(self) -> $t {
+                $t {
+                    x: self.x,
+                    y: -self.y,
+                    z: self.z,
+                }
+            }
+        }
+
+        impl Mul<F> for $t {
+            type Output = TorsionCurve;
+
+            fn mul(self, rhs: F) -> TorsionCurve {
+                TorsionCurve::from(self).mul_scalar(&rhs)
+            }
+        }
+
+        impl<'r> Mul<&'r F> for $t {
+            type Output = TorsionCurve;
+
+            fn mul(self, rhs: &'r F) -> TorsionCurve {
+                TorsionCurve::from(self).mul_scalar(rhs)
+            }
+        }
+    };
+}
+
+impl_choice_traits!(TorsionCurve);
+impl_choice_traits!(TorsionCurveAffine);
+
+/// addition and subtraction of a point of type $rhs to a point of type $lhs, owned and by reference
+macro_rules! impl_add_sub {
+    ($lhs:ident, $rhs:ident) => {
+        impl<'r> Add<&'r $rhs> for $lhs {
+            type Output = TorsionCurve;
+
+            fn add(self, rhs: &'r $rhs) -> TorsionCurve {
+                TorsionCurve::from(self).add_pts(&TorsionCurve::from(*rhs))
+            }
+        }
+
+        impl Add<$rhs> for $lhs {
+            type Output = TorsionCurve;
+
+            fn add(self, rhs: $rhs) -> TorsionCurve {
+                self + &rhs
+            }
+        }
+
+        impl<'r> Sub<&'r $rhs> for $lhs {
+            type Output = TorsionCurve;
+
+            fn sub(self, rhs: &'r $rhs) -> TorsionCurve {
+                self + &(-*rhs)
+            }
+        }
+
+        impl Sub<$rhs> for $lhs {
+            type Output = TorsionCurve;
+
+            fn sub(self, rhs: $rhs) -> TorsionCurve {
+                self + &(-rhs)
+            }
+        }
+    };
+}
+
+impl_add_sub!(TorsionCurve, TorsionCurve);
+impl_add_sub!(TorsionCurve, TorsionCurveAffine);
+impl_add_sub!(TorsionCurveAffine, TorsionCurveAffine);
+
+macro_rules! impl_assign {
+    ($rhs:ident) => {
+        impl<'r> AddAssign<&'r $rhs> for TorsionCurve {
+            fn add_assign(&mut self, rhs: &'r $rhs) {
+                *self = *self + rhs;
+            }
+        }
+
+        impl AddAssign<$rhs> for TorsionCurve {
+            fn add_assign(&mut self, rhs: $rhs) {
+                *self = *self + &rhs;
+            }
+        }
+
+        impl<'r> SubAssign<&'r $rhs> for TorsionCurve {
+            fn sub_assign(&mut self, rhs: &'r $rhs) {
+                *self = *self - rhs;
+            }
+        }
+
+        impl SubAssign<$rhs> for TorsionCurve {
+            fn sub_assign(&mut self, rhs: $rhs) {
+                *self = *self - &rhs;
+            }
+        }
+    };
+}
+
+impl_assign!(TorsionCurve);
+impl_assign!(TorsionCurveAffine);
+
+impl MulAssign<F> for TorsionCurve {
+    fn mul_assign(&mut self, rhs: F) {
+        *self = self.mul_scalar(&rhs);
+    }
+}
+
+impl<'r> MulAssign<&'r F> for TorsionCurve {
+    fn mul_assign(&mut self, rhs: &'r F) {
+        *self = self.mul_scalar(rhs);
+    }
+}
+
+impl Sum for TorsionCurve {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::identity(), |acc, pt| acc + pt)
+    }
+}
+
+impl<'a> Sum<&'a TorsionCurve> for TorsionCurve {
+    fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.fold(Self::identity(), |acc, pt| acc + pt)
+    }
+}